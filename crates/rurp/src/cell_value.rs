@@ -0,0 +1,90 @@
+use std::fmt::Debug;
+use std::ops::Add;
+
+use crate::equivalent;
+
+/// A type that can be stored in the cells of a grid.
+pub trait CellValue:
+    Copy + PartialOrd + Add<Output = Self> + Debug + Send + Sync + 'static
+{
+    /// Convert the value to an f64.
+    fn to_f64(self) -> f64;
+
+    /// Convert an f64 to the value.
+    ///
+    /// Integer types round to the nearest value and saturate at their limits; NaN becomes zero.
+    fn from_f64(value: f64) -> Self;
+
+    /// Append the little-endian bytes of the value to `bytes`.
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>);
+
+    /// Test if two values are equivalent, for comparing cell values to the nodata value.
+    fn equivalent(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+impl CellValue for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn equivalent(&self, other: &Self) -> bool {
+        equivalent(self, other)
+    }
+}
+
+impl CellValue for f32 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    /// Values are equivalent if they are within `f32::EPSILON` of each other, or are both NaN.
+    fn equivalent(&self, other: &Self) -> bool {
+        (self - other).abs() <= f32::EPSILON || self.is_nan() && other.is_nan() || self == other
+    }
+}
+
+impl CellValue for i32 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value.round() as i32
+    }
+
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl CellValue for u8 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value.round() as u8
+    }
+
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+}
@@ -0,0 +1,167 @@
+use std::error::Error;
+
+use crate::interpolate::Interpolator;
+use crate::point::Point;
+use rayon::prelude::*;
+
+/// The prediction for a withheld point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Residual {
+    /// The index of the point in the input slice.
+    pub index: usize,
+    pub observed: f64,
+    pub predicted: f64,
+}
+
+impl Residual {
+    /// Get the residual, the predicted value minus the observed value.
+    #[must_use]
+    pub fn residual(&self) -> f64 {
+        self.predicted - self.observed
+    }
+}
+
+/// The result of cross-validating an interpolation method.
+#[derive(Debug, Clone)]
+pub struct CrossValidation {
+    residuals: Vec<Residual>,
+    unpredicted: Vec<usize>,
+}
+
+impl CrossValidation {
+    /// Get the residuals of the points that could be predicted, ordered by point index.
+    #[must_use]
+    pub fn residuals(&self) -> &[Residual] {
+        &self.residuals
+    }
+
+    /// Get the indices of the points that could not be predicted.
+    ///
+    /// These points are excluded from every error statistic.
+    #[must_use]
+    pub fn unpredicted(&self) -> &[usize] {
+        &self.unpredicted
+    }
+
+    /// Get the mean absolute error.
+    #[must_use]
+    pub fn mae(&self) -> f64 {
+        self.mean(|residual| residual.residual().abs())
+    }
+
+    /// Get the root mean squared error.
+    #[must_use]
+    pub fn rmse(&self) -> f64 {
+        self.mean(|residual| residual.residual().powi(2)).sqrt()
+    }
+
+    /// Get the mean error, positive when the method overestimates.
+    #[must_use]
+    pub fn bias(&self) -> f64 {
+        self.mean(Residual::residual)
+    }
+
+    /// Get the coefficient of determination.
+    ///
+    /// Returns NaN if the observed values have no variance.
+    #[must_use]
+    pub fn r_squared(&self) -> f64 {
+        let observed_mean = self.mean(|residual| residual.observed);
+        let total_sum_of_squares: f64 = self
+            .residuals
+            .iter()
+            .map(|residual| (residual.observed - observed_mean).powi(2))
+            .sum();
+        if total_sum_of_squares == 0. {
+            return f64::NAN;
+        }
+        let residual_sum_of_squares: f64 = self
+            .residuals
+            .iter()
+            .map(|residual| residual.residual().powi(2))
+            .sum();
+        1. - residual_sum_of_squares / total_sum_of_squares
+    }
+
+    fn mean(&self, f: impl Fn(&Residual) -> f64) -> f64 {
+        self.residuals.iter().map(f).sum::<f64>() / self.residuals.len() as f64
+    }
+}
+
+/// Cross-validates an interpolation method by withholding each point in turn and predicting it from the rest.
+///
+/// # Errors
+/// Returns an error if there are fewer than two points or the method fails.
+pub fn leave_one_out(
+    interpolator: &(impl Interpolator + ?Sized),
+    points: &[Point],
+) -> Result<CrossValidation, Box<dyn Error>> {
+    k_fold(interpolator, points, points.len())
+}
+
+/// Cross-validates an interpolation method by splitting the points into `folds` folds,
+/// withholding each fold in turn and predicting it from the rest.
+///
+/// Point `i` is assigned to fold `i % folds`.
+///
+/// # Errors
+/// Returns an error if `folds` is less than two or greater than the number of points, or the method fails.
+pub fn k_fold(
+    interpolator: &(impl Interpolator + ?Sized),
+    points: &[Point],
+    folds: usize,
+) -> Result<CrossValidation, Box<dyn Error>> {
+    if folds < 2 {
+        return Err("At least two folds are required".into());
+    }
+    if folds > points.len() {
+        return Err("There must be at least as many points as folds".into());
+    }
+
+    let predictions = (0..folds)
+        .into_par_iter()
+        .map(|fold| {
+            let (withheld, training): (Vec<_>, Vec<_>) = points
+                .iter()
+                .enumerate()
+                .partition(|(idx, _)| idx % folds == fold);
+            let training: Vec<Point> = training
+                .into_iter()
+                .map(|(_, point)| point.clone())
+                .collect();
+            let locations: Vec<(f64, f64)> =
+                withheld.iter().map(|(_, point)| (*point).into()).collect();
+            interpolator
+                .predict(&training, &locations)
+                .map(|predicted| {
+                    withheld
+                        .iter()
+                        .map(|(idx, _)| *idx)
+                        .zip(predicted)
+                        .collect::<Vec<_>>()
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err as Box<dyn Error>)?;
+
+    let mut predictions: Vec<(usize, Option<f64>)> = predictions.into_iter().flatten().collect();
+    predictions.sort_by_key(|(idx, _)| *idx);
+
+    let mut residuals = vec![];
+    let mut unpredicted = vec![];
+    predictions
+        .into_iter()
+        .for_each(|(index, predicted)| match predicted {
+            Some(predicted) if !predicted.is_nan() => residuals.push(Residual {
+                index,
+                observed: points[index].values[0],
+                predicted,
+            }),
+            _ => unpredicted.push(index),
+        });
+
+    Ok(CrossValidation {
+        residuals,
+        unpredicted,
+    })
+}
@@ -1,4 +1,4 @@
-use crate::{equivalent, grid::Grid, normalize};
+use crate::{cell_value::CellValue, grid::Grid, normalize};
 use image::ImageBuffer;
 
 use std::error::Error;
@@ -6,7 +6,7 @@ use std::error::Error;
 /// Write grid data to an image file.
 /// # Errors
 /// Returns an error if the image file cannot be written.
-pub fn write_grid_data(grid: &Grid, path: &str) -> Result<(), Box<dyn Error>> {
+pub fn write_grid_data<T: CellValue>(grid: &Grid<T>, path: &str) -> Result<(), Box<dyn Error>> {
     let height = grid.height();
     let width = grid.width();
     let grid_data = grid.data();
@@ -15,10 +15,10 @@ pub fn write_grid_data(grid: &Grid, path: &str) -> Result<(), Box<dyn Error>> {
     let data_domain = grid_data
         .iter()
         .fold((f64::INFINITY, f64::NEG_INFINITY), |acc, &v| {
-            if equivalent(&v, &nodata) {
+            if v.equivalent(&nodata) {
                 acc
             } else {
-                (acc.0.min(v), acc.1.max(v))
+                (acc.0.min(v.to_f64()), acc.1.max(v.to_f64()))
             }
         });
 
@@ -26,10 +26,10 @@ pub fn write_grid_data(grid: &Grid, path: &str) -> Result<(), Box<dyn Error>> {
 
     let img = ImageBuffer::from_fn(width as u32, height as u32, |x, y| {
         let cell_value = grid_data[[height - 1 - y as usize, x as usize, 0]];
-        let pixel_value = if equivalent(&cell_value, &nodata) {
+        let pixel_value = if cell_value.equivalent(&nodata) {
             0
         } else {
-            normalize(cell_value, &data_domain, &pixel_domain).round() as u16
+            normalize(cell_value.to_f64(), &data_domain, &pixel_domain).round() as u16
         };
         image::Luma([pixel_value])
     });
@@ -1,53 +1,114 @@
 use std::error::Error;
+use std::ops::Add;
 
 use crate::bounds::Bounds;
-use euclid::Transform2D;
-use geo::Polygon;
+use crate::cell_value::CellValue;
+use crate::grid::rasterize::{MergeMode, RasterizeOptions};
+use crate::resolution::{ExtentPolicy, Resolution};
+use euclid::{Point2D, Transform2D};
+use geo::{Geometry, Polygon};
 use geo_rasterize::{LabelBuilder, Rasterizer};
 use ndarray::{prelude::*, ArrayViewMut3};
 
+pub mod contour;
+pub mod cube;
+pub mod focal;
+pub mod mask;
+pub mod polygonize;
+pub mod rasterize;
+pub mod resample;
+pub mod sample;
+pub mod terrain;
+pub mod tiled;
+pub mod zonal;
+
 pub struct WorldSpace;
 pub struct ScreenSpace;
 
+/// How the cells of a grid are registered to its bounds.
+///
+/// In screen space, cell `(i, j)` always covers `[j, j + 1) x [i, i + 1)`
+/// and its coordinates are those of its center, `(j + 0.5, i + 0.5)`.
+/// Registration decides where that lands in world space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Registration {
+    /// Cells are areas and the bounds are their outer edges.
+    /// Cell coordinates are half a cell inside the bounds.
+    #[default]
+    PixelIsArea,
+    /// Cells are samples at points and the bounds are the outermost points.
+    /// Cell coordinates lie on the bounds and cells extend half a cell beyond them.
+    PixelIsPoint,
+}
+
+/// A raster of cells with one or more bands of values of type `T`.
 #[derive(Debug)]
-pub struct Grid {
-    data: Array3<f64>,
-    x: Array2<f64>,
-    y: Array2<f64>,
+pub struct Grid<T: CellValue = f64> {
+    data: Array3<T>,
+    /// The x coordinate of each column.
+    x: Array1<f64>,
+    /// The y coordinate of each row.
+    y: Array1<f64>,
     bounds: Bounds,
     height: usize,
     width: usize,
     world_height: f64,
     world_width: f64,
+    cell_height: f64,
+    cell_width: f64,
+    registration: Registration,
     world_to_screen_transform: Transform2D<f64, WorldSpace, ScreenSpace>,
     screen_to_world_transform: Transform2D<f64, ScreenSpace, WorldSpace>,
-    nodata: f64,
+    nodata: T,
 }
-impl Grid {
+// The views borrow from the grid through the elided lifetime of `&self`.
+#[allow(mismatched_lifetime_syntaxes)]
+impl<T: CellValue> Grid<T> {
     /// Get a view of grid data.
     #[must_use]
-    pub fn data(&self) -> ArrayView3<f64> {
+    pub fn data(&self) -> ArrayView3<T> {
         self.data.view()
     }
 
     /// Get a mutable view of grid data.
-    pub fn data_mut(&mut self) -> ArrayViewMut3<f64> {
+    pub fn data_mut(&mut self) -> ArrayViewMut3<T> {
         self.data.view_mut()
     }
 
     /// Get a view of x values.
+    ///
+    /// The view is broadcast from the x coordinate of each column, so it takes no extra memory.
     #[must_use]
     pub fn x(&self) -> ArrayView2<f64> {
-        self.x.view()
+        self.x
+            .broadcast((self.height, self.width))
+            .expect("x coordinates broadcast to the grid shape")
     }
 
     /// Get a view of y values.
+    ///
+    /// The view is broadcast from the y coordinate of each row, so it takes no extra memory.
     #[must_use]
     pub fn y(&self) -> ArrayView2<f64> {
+        broadcast_rows(&self.y, self.width)
+    }
+
+    /// Get a view of the x coordinate of each column.
+    #[must_use]
+    pub fn x_coords(&self) -> ArrayView1<'_, f64> {
+        self.x.view()
+    }
+
+    /// Get a view of the y coordinate of each row.
+    #[must_use]
+    pub fn y_coords(&self) -> ArrayView1<'_, f64> {
         self.y.view()
     }
 
     /// Get the bounds of the grid.
+    ///
+    /// With `Registration::PixelIsPoint` these are the outermost cell centers,
+    /// and the cells extend half a cell beyond them.
     #[must_use]
     pub fn bounds(&self) -> Bounds {
         self.bounds.clone()
@@ -65,18 +126,48 @@ impl Grid {
         self.width
     }
 
-    /// Get the height of the grid in world units.
+    /// Get the number of bands in the grid.
+    #[must_use]
+    pub fn bands(&self) -> usize {
+        self.data.len_of(Axis(2))
+    }
+
+    /// Get the height of the grid in world units, the height of its bounds.
+    ///
+    /// With `Registration::PixelIsPoint` this is from the bottom to the top cell center,
+    /// one cell less than the height the cells cover.
     #[must_use]
     pub fn world_height(&self) -> f64 {
         self.world_height
     }
 
-    /// Get the width of the grid in world units.
+    /// Get the width of the grid in world units, the width of its bounds.
+    ///
+    /// With `Registration::PixelIsPoint` this is from the left to the right cell center,
+    /// one cell less than the width the cells cover.
     #[must_use]
     pub fn world_width(&self) -> f64 {
         self.world_width
     }
 
+    /// Get the width of a cell in world units.
+    #[must_use]
+    pub fn cell_width(&self) -> f64 {
+        self.cell_width
+    }
+
+    /// Get the height of a cell in world units.
+    #[must_use]
+    pub fn cell_height(&self) -> f64 {
+        self.cell_height
+    }
+
+    /// Get the registration of the cells to the bounds.
+    #[must_use]
+    pub fn registration(&self) -> Registration {
+        self.registration
+    }
+
     /// Get the transformation from world space to screen space.s
     #[must_use]
     pub fn screen_to_world_transform(&self) -> Transform2D<f64, ScreenSpace, WorldSpace> {
@@ -91,44 +182,137 @@ impl Grid {
 
     /// Get the nodata value.
     #[must_use]
-    pub fn nodata(&self) -> f64 {
+    pub fn nodata(&self) -> T {
         self.nodata
     }
 }
 
-impl Grid {
+impl<T: CellValue> Grid<T> {
     /// Create a new empty Grid instance with given bounds, resolution, and nodata value.
     ///
+    /// Cells are registered as `Registration::PixelIsArea`.
+    /// The number of cells is rounded up to cover the bounds,
+    /// and the cells are stretched to fit the bounds exactly,
+    /// so cells can be slightly smaller than `resolution`.
+    /// Use `empty_from_resolution` for exact or non-square cell sizes.
+    ///
     /// # Errors
     /// Returns an error if the grid cannot be created.
+    #[deprecated(
+        note = "cells are silently stretched to fit the bounds; use `empty_from_resolution` with an `ExtentPolicy` instead"
+    )]
     pub fn empty_from_bounds(
         bounds: &Bounds,
         resolution: usize,
-        nodata: f64,
+        nodata: T,
+    ) -> Result<Self, Box<dyn Error>> {
+        let height = ((bounds.top() - bounds.bottom()) / resolution as f64).ceil() as usize;
+        let width = ((bounds.right() - bounds.left()) / resolution as f64).ceil() as usize;
+
+        let cell_height = (bounds.top() - bounds.bottom()) / height as f64;
+        let cell_width = (bounds.right() - bounds.left()) / width as f64;
+
+        Grid::from_geometry(
+            bounds,
+            (height, width),
+            (cell_height, cell_width),
+            Registration::PixelIsArea,
+            1,
+            nodata,
+        )
+    }
+
+    /// Create a new empty Grid instance with cells of exactly the given resolution.
+    ///
+    /// The grid is anchored at the left and bottom bounds.
+    /// If the bounds are not a whole number of cells wide or high,
+    /// the right and top bounds are adjusted according to `policy`.
+    /// With `Registration::PixelIsPoint` there is one more row and column than whole cells,
+    /// since the bounds are the outermost cell centers.
+    ///
+    /// # Errors
+    /// Returns an error if the bounds do not fit the resolution under `policy`
+    /// or the grid cannot be created.
+    pub fn empty_from_resolution(
+        bounds: &Bounds,
+        resolution: &Resolution,
+        policy: ExtentPolicy,
+        registration: Registration,
+        nodata: T,
+    ) -> Result<Self, Box<dyn Error>> {
+        Grid::from_resolution_with_bands(bounds, resolution, policy, registration, 1, nodata)
+    }
+
+    /// Create a new Grid instance like `empty_from_resolution` with `bands` bands.
+    fn from_resolution_with_bands(
+        bounds: &Bounds,
+        resolution: &Resolution,
+        policy: ExtentPolicy,
+        registration: Registration,
+        bands: usize,
+        nodata: T,
     ) -> Result<Self, Box<dyn Error>> {
         let (left, bottom, right, top) = bounds.clone().into();
 
-        let world_height = top - bottom;
-        let world_width = right - left;
+        let columns = policy.cell_count(right - left, resolution.x())?;
+        let rows = policy.cell_count(top - bottom, resolution.y())?;
 
-        let height = (world_height / resolution as f64).ceil() as usize;
-        let width = (world_width / resolution as f64).ceil() as usize;
+        let fitted_bounds = Bounds::new(
+            left,
+            bottom,
+            left + columns as f64 * resolution.x(),
+            bottom + rows as f64 * resolution.y(),
+        )?;
+
+        let (height, width) = match registration {
+            Registration::PixelIsArea => (rows, columns),
+            Registration::PixelIsPoint => (rows + 1, columns + 1),
+        };
+
+        Grid::from_geometry(
+            &fitted_bounds,
+            (height, width),
+            (resolution.y(), resolution.x()),
+            registration,
+            bands,
+            nodata,
+        )
+    }
 
-        let data = Array3::from_elem((height, width, 1), nodata);
+    /// Create a new Grid instance with `bands` bands filled with the nodata value.
+    fn from_geometry(
+        bounds: &Bounds,
+        (height, width): (usize, usize),
+        (cell_height, cell_width): (f64, f64),
+        registration: Registration,
+        bands: usize,
+        nodata: T,
+    ) -> Result<Self, Box<dyn Error>> {
+        let (left, bottom, right, top) = bounds.clone().into();
 
-        let x_vals = Array1::linspace(left, right, width);
-        let x = Array2::from_shape_fn((height, width), |(_, j)| x_vals[j]);
+        let world_height = top - bottom;
+        let world_width = right - left;
 
-        let y_vals = Array1::linspace(bottom, top, height);
-        let y = Array2::from_shape_fn((height, width), |(i, _)| y_vals[i]);
+        let (origin_x, origin_y) = match registration {
+            Registration::PixelIsArea => (left, bottom),
+            Registration::PixelIsPoint => (left - cell_width / 2., bottom - cell_height / 2.),
+        };
 
         let world_to_screen_transform: Transform2D<f64, WorldSpace, ScreenSpace> =
-            Transform2D::translation(-left, -bottom)
-                .then_scale(width as f64 / world_width, height as f64 / world_height);
+            Transform2D::translation(-origin_x, -origin_y)
+                .then_scale(1. / cell_width, 1. / cell_height);
         let screen_to_world_transform = world_to_screen_transform
             .inverse()
             .ok_or("Error creating screen to world transform".to_string())?;
 
+        let data = Array3::from_elem((height, width, bands), nodata);
+
+        let cell_center = |i: usize, j: usize| {
+            screen_to_world_transform.transform_point(Point2D::new(j as f64 + 0.5, i as f64 + 0.5))
+        };
+        let x = Array1::from_shape_fn(width, |j| cell_center(0, j).x);
+        let y = Array1::from_shape_fn(height, |i| cell_center(i, 0).y);
+
         Ok(Grid {
             data,
             x,
@@ -138,6 +322,9 @@ impl Grid {
             width,
             world_height,
             world_width,
+            cell_height,
+            cell_width,
+            registration,
             world_to_screen_transform,
             screen_to_world_transform,
             nodata,
@@ -145,49 +332,140 @@ impl Grid {
     }
 }
 
-impl Grid {
-    fn build_default_rasterizer(&self) -> Result<Rasterizer<f64>, geo_rasterize::RasterizeError> {
+impl<T: CellValue> Grid<T> {
+    /// Build a rasterizer matching the grid with labels of any type.
+    fn build_rasterizer<L>(
+        &self,
+        background: L,
+    ) -> Result<Rasterizer<L>, geo_rasterize::RasterizeError>
+    where
+        L: Copy + Add<Output = L>,
+    {
         let geo_pix_transform = self.world_to_screen_transform.to_untyped();
-        LabelBuilder::background(self.nodata)
+        LabelBuilder::background(background)
             .width(self.width)
             .height(self.height)
             .geo_to_pix(geo_pix_transform)
             .build()
     }
 
-    /// Rasterize polygons onto the grid.
+    /// Rasterize polygons onto the first band of the grid.
     /// The polygons are assumed to be in world space.
     ///
+    /// Every cell a polygon touches is set to its label, later polygons replacing earlier ones.
+    /// Other cells are left unchanged.
+    /// Use `rasterize_geometries` for other geometries, another band or coverage fractions.
+    ///
     /// # Errors
-    /// Returns an error if the rasterization fails.
+    /// Returns an error if there is not one label per polygon, the grid has no bands
+    /// or the rasterization fails.
     pub fn rasterize_polygons(
         &mut self,
         polygons: &[Polygon<f64>],
-        polygon_labels: &[f64],
+        polygon_labels: &[T],
     ) -> Result<(), Box<dyn Error>> {
-        let mut rasterizer = self.build_default_rasterizer()?;
-
-        polygons.iter().zip(polygon_labels).try_for_each(
-            |(polygon, polygon_label)| -> Result<(), geo_rasterize::RasterizeError> {
-                rasterizer.rasterize(polygon, *polygon_label)?;
-                Ok(())
+        let geometries: Vec<Geometry<f64>> = polygons.iter().cloned().map(Into::into).collect();
+        self.rasterize_geometries(
+            &geometries,
+            polygon_labels,
+            RasterizeOptions {
+                merge: MergeMode::Replace,
+                all_touched: true,
+                ..Default::default()
             },
-        )?;
+        )
+    }
+}
+
+impl<T: CellValue> Grid<T> {
+    /// Replace the grid data with `bands` bands filled with the nodata value.
+    pub fn reset_bands(&mut self, bands: usize) {
+        self.data = Array3::from_elem((self.height, self.width, bands), self.nodata);
+    }
+
+    /// Convert the grid to another cell type.
+    ///
+    /// Nodata cells become `nodata` and other cells are converted with `CellValue::from_f64`,
+    /// so converting to an integer type rounds and saturates.
+    #[must_use]
+    pub fn cast<U: CellValue>(&self, nodata: U) -> Grid<U> {
+        let data = self.data.mapv(|value| {
+            if value.equivalent(&self.nodata) {
+                nodata
+            } else {
+                U::from_f64(value.to_f64())
+            }
+        });
+        self.with_data(data, nodata)
+    }
+
+    /// Create a grid with the geometry of this grid and the given data.
+    fn with_data<U: CellValue>(&self, data: Array3<U>, nodata: U) -> Grid<U> {
+        Grid {
+            data,
+            x: self.x.clone(),
+            y: self.y.clone(),
+            bounds: self.bounds.clone(),
+            height: self.height,
+            width: self.width,
+            world_height: self.world_height,
+            world_width: self.world_width,
+            cell_height: self.cell_height,
+            cell_width: self.cell_width,
+            registration: self.registration,
+            world_to_screen_transform: self.world_to_screen_transform,
+            screen_to_world_transform: self.screen_to_world_transform,
+            nodata,
+        }
+    }
 
-        self.data = rasterizer
-            .finish()
-            .into_shape((self.height, self.width, 1))?;
-        Ok(())
+    /// Test if another grid has the same cells in world space as this grid.
+    fn same_geometry<U: CellValue>(&self, other: &Grid<U>) -> bool {
+        self.height == other.height
+            && self.width == other.width
+            && self.registration == other.registration
+            && self.world_to_screen_transform == other.world_to_screen_transform
     }
 }
 
-impl Grid {
+impl<T: CellValue> Grid<T> {
     /// Get an iterator over the world space coordinates and data values.
-    pub fn iter_world_mut(&mut self) -> impl Iterator<Item = (f64, f64, &mut f64)> {
-        self.x
-            .iter()
-            .zip(self.y.iter())
-            .zip(self.data.iter_mut())
+    ///
+    /// Only the first band is visited when the grid has more than one band.
+    pub fn iter_world_mut(&mut self) -> impl Iterator<Item = (f64, f64, &mut T)> {
+        let shape = (self.height, self.width);
+        let x = self
+            .x
+            .broadcast(shape)
+            .expect("x coordinates broadcast to the grid shape");
+        let y = broadcast_rows(&self.y, self.width);
+        x.into_iter()
+            .zip(y)
+            .zip(self.data.index_axis_mut(Axis(2), 0))
+            .map(|((x, y), data)| (*x, *y, data))
+    }
+
+    /// Get an iterator over the world space coordinates and the values of every band in each cell.
+    pub fn iter_world_bands_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (f64, f64, ArrayViewMut1<'_, T>)> {
+        let shape = (self.height, self.width);
+        let x = self
+            .x
+            .broadcast(shape)
+            .expect("x coordinates broadcast to the grid shape");
+        let y = broadcast_rows(&self.y, self.width);
+        x.into_iter()
+            .zip(y)
+            .zip(self.data.lanes_mut(Axis(2)))
             .map(|((x, y), data)| (*x, *y, data))
     }
 }
+
+/// Broadcast the coordinate of each row across `width` columns, without copying it.
+fn broadcast_rows(rows: &Array1<f64>, width: usize) -> ArrayView2<'_, f64> {
+    // Broadcasting only adds leading axes, so broadcast to columns of rows and swap the axes.
+    rows.broadcast((width, rows.len()))
+        .expect("row coordinates broadcast to the grid shape")
+        .reversed_axes()
+}
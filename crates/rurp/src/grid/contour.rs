@@ -0,0 +1,408 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use crate::cell_value::CellValue;
+use crate::grid::Grid;
+use crate::rings::{boundary_edges, polygons_from_edges};
+use euclid::Point2D;
+use geo::orient::{Direction, Orient};
+use geo::{Coord, LineString, MapCoords, MultiLineString, MultiPolygon};
+
+/// Options for extracting isolines from a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct IsolineOptions {
+    /// The band to contour.
+    pub band: usize,
+    /// The number of iterations of Chaikin's corner cutting used to smooth the lines.
+    /// Zero keeps the lines as traced.
+    pub smoothing: usize,
+}
+
+/// A grid edge between the cell at a row and column and its neighbor to the right,
+/// or its neighbor above if vertical.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Edge {
+    row: usize,
+    column: usize,
+    vertical: bool,
+}
+
+/// A piece of an isoline crossing one square of cell centers, from one edge to another.
+type Segment = [Edge; 2];
+
+/// A corner of a piece of an isoband, in terms of the cells it is derived from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum BandNode {
+    /// The center of the cell at a row and column.
+    Cell(usize, usize),
+    /// Where a level, as bits, crosses the line from one cell center to another,
+    /// ordered by row then column.
+    Crossing((usize, usize), (usize, usize), u64),
+}
+
+impl<T: CellValue> Grid<T> {
+    /// Extract isolines of a band at each level using marching squares, in the order of the levels.
+    /// The lines are in world space.
+    ///
+    /// Lines connect the cell centers, crossing between two cells where one is below the level
+    /// and the other is at or above it, at the linearly interpolated position.
+    /// Squares of four cell centers with a nodata cell are skipped, so lines end at nodata.
+    /// Lines that close on themselves are returned as closed line strings.
+    ///
+    /// # Arguments
+    /// `levels` - The values to trace lines at.
+    /// `options` - The band to contour and how much to smooth the lines.
+    ///
+    /// # Errors
+    /// Returns an error if the band is out of range or a level is not finite.
+    pub fn isolines(
+        &self,
+        levels: &[f64],
+        options: IsolineOptions,
+    ) -> Result<Vec<MultiLineString<f64>>, Box<dyn Error>> {
+        if options.band >= self.bands() {
+            return Err("Band is out of range".into());
+        }
+        if !levels.iter().all(|level| level.is_finite()) {
+            return Err("Levels must be finite".into());
+        }
+
+        Ok(levels
+            .iter()
+            .map(|&level| {
+                let lines = join_segments(&self.isoline_segments(level, options.band))
+                    .into_iter()
+                    .map(|edges| {
+                        let coords: Vec<Coord<f64>> = edges
+                            .iter()
+                            .map(|&edge| self.crossing(edge, level, options.band))
+                            .collect();
+                        LineString::new(chaikin(coords, options.smoothing))
+                    })
+                    .collect();
+                MultiLineString::new(lines)
+            })
+            .collect())
+    }
+
+    /// Get the value of a band at a cell as f64, or None if it is nodata.
+    fn band_value(&self, row: usize, column: usize, band: usize) -> Option<f64> {
+        let value = self.data[[row, column, band]];
+        (!value.equivalent(&self.nodata)).then(|| value.to_f64())
+    }
+
+    /// Get the isoline segments of each square of cell centers without nodata.
+    fn isoline_segments(&self, level: f64, band: usize) -> Vec<Segment> {
+        let mut segments = vec![];
+        for row in 0..self.height.saturating_sub(1) {
+            for column in 0..self.width.saturating_sub(1) {
+                let corners = [(0, 0), (0, 1), (1, 1), (1, 0)]
+                    .map(|(i, j)| self.band_value(row + i, column + j, band));
+                let [Some(bottom_left), Some(bottom_right), Some(top_right), Some(top_left)] =
+                    corners
+                else {
+                    continue;
+                };
+
+                let bottom = Edge {
+                    row,
+                    column,
+                    vertical: false,
+                };
+                let right = Edge {
+                    row,
+                    column: column + 1,
+                    vertical: true,
+                };
+                let top = Edge {
+                    row: row + 1,
+                    column,
+                    vertical: false,
+                };
+                let left = Edge {
+                    row,
+                    column,
+                    vertical: true,
+                };
+
+                let case = [bottom_left, bottom_right, top_right, top_left]
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &value)| value >= level)
+                    .fold(0, |case, (idx, _)| case | 1 << idx);
+                let center_above =
+                    (bottom_left + bottom_right + top_right + top_left) / 4. >= level;
+                match case {
+                    0 | 15 => {}
+                    1 | 14 => segments.push([left, bottom]),
+                    2 | 13 => segments.push([bottom, right]),
+                    3 | 12 => segments.push([left, right]),
+                    4 | 11 => segments.push([right, top]),
+                    6 | 9 => segments.push([bottom, top]),
+                    7 | 8 => segments.push([left, top]),
+                    // Saddles, where the center decides which corners are connected.
+                    5 if center_above => segments.extend([[left, top], [bottom, right]]),
+                    5 => segments.extend([[left, bottom], [right, top]]),
+                    10 if center_above => segments.extend([[left, bottom], [right, top]]),
+                    _ => segments.extend([[left, top], [bottom, right]]),
+                }
+            }
+        }
+        segments
+    }
+
+    /// Get the world position where a level crosses an edge between two cell centers.
+    fn crossing(&self, edge: Edge, level: f64, band: usize) -> Coord<f64> {
+        let (next_row, next_column) = if edge.vertical {
+            (edge.row + 1, edge.column)
+        } else {
+            (edge.row, edge.column + 1)
+        };
+        let start = self
+            .band_value(edge.row, edge.column, band)
+            .unwrap_or(level);
+        let end = self
+            .band_value(next_row, next_column, band)
+            .unwrap_or(level);
+        let t = if end == start {
+            0.5
+        } else {
+            ((level - start) / (end - start)).clamp(0., 1.)
+        };
+
+        let (x, y) = if edge.vertical {
+            (edge.column as f64 + 0.5, edge.row as f64 + 0.5 + t)
+        } else {
+            (edge.column as f64 + 0.5 + t, edge.row as f64 + 0.5)
+        };
+        let point = self
+            .screen_to_world_transform
+            .transform_point(Point2D::new(x, y));
+        Coord {
+            x: point.x,
+            y: point.y,
+        }
+    }
+}
+
+impl<T: CellValue> Grid<T> {
+    /// Extract isobands of a band between consecutive breaks using marching triangles,
+    /// in the order of the breaks. The polygons are in world space.
+    ///
+    /// Each isoband covers the values from one break, inclusive, to the next, exclusive,
+    /// linearly interpolated over triangles of cell centers. Every square of four cell centers
+    /// is split along its diagonal from the bottom left, and squares with a nodata cell are skipped.
+    /// Neighboring isobands share their boundaries exactly, and the polygons are valid,
+    /// with counterclockwise exteriors and clockwise holes.
+    ///
+    /// # Arguments
+    /// `breaks` - The bounds of the isobands, in increasing order. Infinite bounds are allowed.
+    /// `band` - The band to contour.
+    ///
+    /// # Errors
+    /// Returns an error if the band is out of range, there are fewer than two breaks,
+    /// or the breaks are not strictly increasing.
+    pub fn isobands(
+        &self,
+        breaks: &[f64],
+        band: usize,
+    ) -> Result<Vec<MultiPolygon<f64>>, Box<dyn Error>> {
+        if band >= self.bands() {
+            return Err("Band is out of range".into());
+        }
+        if breaks.len() < 2 {
+            return Err("At least two breaks are required".into());
+        }
+        if !breaks.windows(2).all(|pair| pair[0] < pair[1]) {
+            return Err("Breaks must be strictly increasing".into());
+        }
+
+        let triangles = self.triangles(band);
+        Ok(breaks
+            .windows(2)
+            .map(|pair| {
+                let pieces = triangles
+                    .iter()
+                    .filter_map(|triangle| band_piece(triangle, pair[0], pair[1]));
+                let edges = boundary_edges(pieces);
+                polygons_from_edges(&edges, |node| self.band_node_screen(node, band))
+                    .map_coords(|coord| {
+                        let point = self
+                            .screen_to_world_transform
+                            .transform_point(Point2D::new(coord.x, coord.y));
+                        Coord {
+                            x: point.x,
+                            y: point.y,
+                        }
+                    })
+                    .orient(Direction::Default)
+            })
+            .collect())
+    }
+
+    /// Get the counterclockwise triangles of cell centers without nodata, with their values.
+    fn triangles(&self, band: usize) -> Vec<[((usize, usize), f64); 3]> {
+        let mut triangles = vec![];
+        for row in 0..self.height.saturating_sub(1) {
+            for column in 0..self.width.saturating_sub(1) {
+                let corners = [(0, 0), (0, 1), (1, 1), (1, 0)].map(|(i, j)| {
+                    let cell = (row + i, column + j);
+                    self.band_value(cell.0, cell.1, band)
+                        .map(|value| (cell, value))
+                });
+                let [Some(bottom_left), Some(bottom_right), Some(top_right), Some(top_left)] =
+                    corners
+                else {
+                    continue;
+                };
+                triangles.push([bottom_left, bottom_right, top_right]);
+                triangles.push([bottom_left, top_right, top_left]);
+            }
+        }
+        triangles
+    }
+
+    /// Get the screen position of an isoband node.
+    fn band_node_screen(&self, node: BandNode, band: usize) -> Coord<f64> {
+        let center = |(row, column): (usize, usize)| Coord {
+            x: column as f64 + 0.5,
+            y: row as f64 + 0.5,
+        };
+        match node {
+            BandNode::Cell(row, column) => center((row, column)),
+            BandNode::Crossing(from, to, level) => {
+                let level = f64::from_bits(level);
+                let start = self.band_value(from.0, from.1, band).unwrap_or(level);
+                let end = self.band_value(to.0, to.1, band).unwrap_or(level);
+                let t = (level - start) / (end - start);
+                center(from) + (center(to) - center(from)) * t
+            }
+        }
+    }
+}
+
+/// Get the part of a counterclockwise triangle with values from `lower`, inclusive,
+/// to `upper`, exclusive, as a counterclockwise ring of nodes, or None if it has no area.
+///
+/// The values are linear over the triangle, so the part is convex.
+/// Walking around the triangle, each corner inside the interval is kept,
+/// followed by where each bound crosses the side to the next corner.
+fn band_piece(
+    triangle: &[((usize, usize), f64); 3],
+    lower: f64,
+    upper: f64,
+) -> Option<Vec<BandNode>> {
+    let mut nodes: Vec<BandNode> = vec![];
+    for idx in 0..3 {
+        let (start, start_value) = triangle[idx];
+        let (end, end_value) = triangle[(idx + 1) % 3];
+        if lower <= start_value && start_value < upper {
+            nodes.push(BandNode::Cell(start.0, start.1));
+        }
+
+        let bounds = if start_value < end_value {
+            [lower, upper]
+        } else {
+            [upper, lower]
+        };
+        for level in bounds {
+            if (start_value < level) == (end_value < level) {
+                continue;
+            }
+            // A bound through a corner crosses at that corner.
+            let node = if start_value == level {
+                BandNode::Cell(start.0, start.1)
+            } else if end_value == level {
+                BandNode::Cell(end.0, end.1)
+            } else if start < end {
+                BandNode::Crossing(start, end, level.to_bits())
+            } else {
+                BandNode::Crossing(end, start, level.to_bits())
+            };
+            nodes.push(node);
+        }
+    }
+
+    nodes.dedup();
+    while nodes.len() > 1 && nodes.first() == nodes.last() {
+        nodes.pop();
+    }
+    (nodes.len() >= 3).then_some(nodes)
+}
+
+/// Join segments that share edges into chains of edges.
+///
+/// Every edge is shared by at most two segments, so each chain is either open,
+/// ending where a line leaves the grid or meets nodata, or closed, repeating its first edge.
+fn join_segments(segments: &[Segment]) -> Vec<Vec<Edge>> {
+    let mut segments_by_edge: HashMap<Edge, Vec<usize>> = HashMap::new();
+    for (idx, segment) in segments.iter().enumerate() {
+        for edge in segment {
+            segments_by_edge.entry(*edge).or_default().push(idx);
+        }
+    }
+
+    let mut used = vec![false; segments.len()];
+    // Follow the unused segments from an edge, returning the edges reached.
+    let follow = |mut edge: Edge, used: &mut [bool]| {
+        let mut edges = vec![];
+        while let Some(&next) = segments_by_edge[&edge].iter().find(|&&idx| !used[idx]) {
+            used[next] = true;
+            let [a, b] = segments[next];
+            edge = if a == edge { b } else { a };
+            edges.push(edge);
+        }
+        edges
+    };
+
+    let mut chains = vec![];
+    for idx in 0..segments.len() {
+        if used[idx] {
+            continue;
+        }
+        used[idx] = true;
+        let [start, end] = segments[idx];
+        let forward = follow(end, &mut used);
+        let backward = if forward.last() == Some(&start) {
+            vec![]
+        } else {
+            follow(start, &mut used)
+        };
+
+        let mut chain: Vec<Edge> = backward.into_iter().rev().collect();
+        chain.push(start);
+        chain.push(end);
+        chain.extend(forward);
+        chains.push(chain);
+    }
+    chains
+}
+
+/// Smooth a line with iterations of Chaikin's corner cutting,
+/// replacing each segment with points at one and three quarters of its length.
+///
+/// Open lines keep their end points, and closed lines stay closed.
+fn chaikin(mut coords: Vec<Coord<f64>>, iterations: usize) -> Vec<Coord<f64>> {
+    for _ in 0..iterations {
+        if coords.len() < 3 {
+            break;
+        }
+        let closed = coords.first() == coords.last();
+        let mut smoothed = Vec::with_capacity(coords.len() * 2);
+        if !closed {
+            smoothed.push(coords[0]);
+        }
+        for pair in coords.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            smoothed.push(a * 0.75 + b * 0.25);
+            smoothed.push(a * 0.25 + b * 0.75);
+        }
+        if closed {
+            smoothed.push(smoothed[0]);
+        } else {
+            smoothed.push(coords[coords.len() - 1]);
+        }
+        coords = smoothed;
+    }
+    coords
+}
@@ -0,0 +1,476 @@
+use std::error::Error;
+
+use crate::bounds::Bounds;
+use crate::cell_value::CellValue;
+use crate::grid::{Grid, Registration};
+use crate::Timestamp;
+use ndarray::prelude::*;
+use rayon::prelude::*;
+
+/// The number of seconds in a day.
+pub const SECONDS_PER_DAY: Timestamp = 86_400;
+
+/// The statistic used to aggregate a cell across time steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemporalAggregation {
+    Mean,
+    Min,
+    Max,
+    Sum,
+}
+
+/// The method used to interpolate between time steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemporalInterpolation {
+    /// Linear interpolation between the steps before and after.
+    Linear,
+    /// Cubic Hermite interpolation between the steps before and after,
+    /// with slopes estimated from the steps on either side of them.
+    Cubic,
+}
+
+/// A stack of grids that share one geometry, one per time step.
+///
+/// Steps are ordered by strictly increasing timestamp.
+#[derive(Debug)]
+pub struct GridCube<T: CellValue = f64> {
+    /// The shared geometry, with no bands.
+    geometry: Grid<T>,
+    /// The data of every step, with shape (steps, height, width, bands).
+    data: Array4<T>,
+    timestamps: Vec<Timestamp>,
+}
+
+impl<T: CellValue> GridCube<T> {
+    /// Get a view of the data of every step, with shape (steps, height, width, bands).
+    #[must_use]
+    pub fn data(&self) -> ArrayView4<'_, T> {
+        self.data.view()
+    }
+
+    /// Get a mutable view of the data of every step.
+    pub fn data_mut(&mut self) -> ArrayViewMut4<'_, T> {
+        self.data.view_mut()
+    }
+
+    /// Get the timestamp of each step.
+    #[must_use]
+    pub fn timestamps(&self) -> &[Timestamp] {
+        &self.timestamps
+    }
+
+    /// Get the number of time steps.
+    #[must_use]
+    pub fn steps(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    /// Get a view of x values.
+    #[must_use]
+    pub fn x(&self) -> ArrayView2<'_, f64> {
+        self.geometry.x()
+    }
+
+    /// Get a view of y values.
+    #[must_use]
+    pub fn y(&self) -> ArrayView2<'_, f64> {
+        self.geometry.y()
+    }
+
+    /// Get the bounds of the grids.
+    #[must_use]
+    pub fn bounds(&self) -> Bounds {
+        self.geometry.bounds()
+    }
+
+    /// Get the height of the grids.
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.geometry.height()
+    }
+
+    /// Get the width of the grids.
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.geometry.width()
+    }
+
+    /// Get the number of bands in each step.
+    #[must_use]
+    pub fn bands(&self) -> usize {
+        self.data.len_of(Axis(3))
+    }
+
+    /// Get the width of a cell in world units.
+    #[must_use]
+    pub fn cell_width(&self) -> f64 {
+        self.geometry.cell_width()
+    }
+
+    /// Get the height of a cell in world units.
+    #[must_use]
+    pub fn cell_height(&self) -> f64 {
+        self.geometry.cell_height()
+    }
+
+    /// Get the registration of the cells to the bounds.
+    #[must_use]
+    pub fn registration(&self) -> Registration {
+        self.geometry.registration()
+    }
+
+    /// Get the nodata value.
+    #[must_use]
+    pub fn nodata(&self) -> T {
+        self.geometry.nodata()
+    }
+}
+
+impl<T: CellValue> GridCube<T> {
+    /// Create a new GridCube instance with the geometry of `grid`,
+    /// and `bands` bands filled with the nodata value at each timestamp.
+    ///
+    /// # Errors
+    /// Returns an error if the timestamps are not strictly increasing.
+    pub fn empty_from_grid(
+        grid: &Grid<T>,
+        timestamps: Vec<Timestamp>,
+        bands: usize,
+    ) -> Result<Self, Box<dyn Error>> {
+        check_increasing(&timestamps)?;
+        let data = Array4::from_elem(
+            (timestamps.len(), grid.height, grid.width, bands),
+            grid.nodata,
+        );
+        Ok(GridCube {
+            geometry: grid.with_data(
+                Array3::from_elem((grid.height, grid.width, 0), grid.nodata),
+                grid.nodata,
+            ),
+            data,
+            timestamps,
+        })
+    }
+
+    /// Create a new GridCube instance from a grid for each timestamp.
+    ///
+    /// The steps are ordered by timestamp.
+    ///
+    /// # Errors
+    /// Returns an error if there are no grids, timestamps are repeated,
+    /// or the grids do not share one geometry, band count and nodata value.
+    pub fn from_grids(mut grids: Vec<(Timestamp, Grid<T>)>) -> Result<Self, Box<dyn Error>> {
+        grids.sort_by_key(|(timestamp, _)| *timestamp);
+        let (_, first) = grids.first().ok_or("At least one grid is required")?;
+        if grids.iter().any(|(_, grid)| !first.same_geometry(grid)) {
+            return Err("Grids must share one geometry".into());
+        }
+        if grids.iter().any(|(_, grid)| grid.bands() != first.bands()) {
+            return Err("Grids must have the same number of bands".into());
+        }
+        if grids
+            .iter()
+            .any(|(_, grid)| !grid.nodata.equivalent(&first.nodata))
+        {
+            return Err("Grids must have the same nodata value".into());
+        }
+
+        let timestamps: Vec<Timestamp> = grids.iter().map(|(timestamp, _)| *timestamp).collect();
+        check_increasing(&timestamps)?;
+
+        let views: Vec<ArrayView3<'_, T>> = grids.iter().map(|(_, grid)| grid.data()).collect();
+        let data = ndarray::stack(Axis(0), &views)?;
+        let geometry = first.with_data(
+            Array3::from_elem((first.height, first.width, 0), first.nodata),
+            first.nodata,
+        );
+
+        Ok(GridCube {
+            geometry,
+            data,
+            timestamps,
+        })
+    }
+}
+
+impl<T: CellValue> GridCube<T> {
+    /// Get the grid of the step at `index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of range.
+    #[must_use]
+    pub fn step(&self, index: usize) -> Grid<T> {
+        self.geometry.with_data(
+            self.data.index_axis(Axis(0), index).to_owned(),
+            self.nodata(),
+        )
+    }
+
+    /// Get the grid of the step at `timestamp`, if there is one.
+    #[must_use]
+    pub fn at(&self, timestamp: Timestamp) -> Option<Grid<T>> {
+        self.timestamps
+            .binary_search(&timestamp)
+            .ok()
+            .map(|index| self.step(index))
+    }
+
+    /// Get a cube of the steps with timestamps in `[start, end)`.
+    ///
+    /// The cube has no steps if none are in range.
+    #[must_use]
+    pub fn slice_time(&self, start: Timestamp, end: Timestamp) -> GridCube<T> {
+        let first = self
+            .timestamps
+            .partition_point(|&timestamp| timestamp < start);
+        let last = self
+            .timestamps
+            .partition_point(|&timestamp| timestamp < end)
+            .max(first);
+        GridCube {
+            geometry: self
+                .geometry
+                .with_data(self.geometry.data.clone(), self.nodata()),
+            data: self.data.slice(s![first..last, .., .., ..]).to_owned(),
+            timestamps: self.timestamps[first..last].to_vec(),
+        }
+    }
+
+    /// Aggregate every step into a single grid.
+    ///
+    /// Nodata values are ignored. Cells that are nodata at every step are nodata.
+    /// Sums and means are calculated as f64 and converted back with `CellValue::from_f64`.
+    #[must_use]
+    pub fn aggregate(&self, aggregation: TemporalAggregation) -> Grid<T> {
+        self.aggregate_steps(0..self.steps(), aggregation)
+    }
+
+    /// Aggregate the steps within each period into a cube with one step per period.
+    ///
+    /// Periods are `period` seconds long and aligned to the Unix epoch.
+    /// Each step of the result is stamped with the start of its period,
+    /// and periods without steps are omitted.
+    ///
+    /// # Errors
+    /// Returns an error if `period` is not positive.
+    pub fn aggregate_by_period(
+        &self,
+        period: Timestamp,
+        aggregation: TemporalAggregation,
+    ) -> Result<GridCube<T>, Box<dyn Error>> {
+        if period <= 0 {
+            return Err("Period must be positive".into());
+        }
+
+        let mut timestamps = vec![];
+        let mut grids = vec![];
+        let mut first = 0;
+        while first < self.steps() {
+            let period_start = self.timestamps[first].div_euclid(period) * period;
+            let last = self
+                .timestamps
+                .partition_point(|&timestamp| timestamp < period_start + period);
+            timestamps.push(period_start);
+            grids.push(self.aggregate_steps(first..last, aggregation).data);
+            first = last;
+        }
+
+        let views: Vec<ArrayView3<'_, T>> = grids.iter().map(ArrayBase::view).collect();
+        let data = if views.is_empty() {
+            Array4::from_elem(
+                (0, self.height(), self.width(), self.bands()),
+                self.nodata(),
+            )
+        } else {
+            ndarray::stack(Axis(0), &views)?
+        };
+        Ok(GridCube {
+            geometry: self
+                .geometry
+                .with_data(self.geometry.data.clone(), self.nodata()),
+            data,
+            timestamps,
+        })
+    }
+
+    /// Aggregate the steps within each UTC day into a cube with one step per day.
+    ///
+    /// # Errors
+    /// Returns an error if the cube cannot be aggregated.
+    pub fn aggregate_daily(
+        &self,
+        aggregation: TemporalAggregation,
+    ) -> Result<GridCube<T>, Box<dyn Error>> {
+        self.aggregate_by_period(SECONDS_PER_DAY, aggregation)
+    }
+
+    fn aggregate_steps(
+        &self,
+        steps: std::ops::Range<usize>,
+        aggregation: TemporalAggregation,
+    ) -> Grid<T> {
+        let (height, width, bands) = (self.height(), self.width(), self.bands());
+        let nodata = self.nodata();
+        let steps = self.data.slice(s![steps, .., .., ..]);
+
+        let values: Vec<T> = (0..height * width * bands)
+            .into_par_iter()
+            .map(|idx| {
+                let (row, column, band) = (idx / (width * bands), idx / bands % width, idx % bands);
+                let series = steps
+                    .slice(s![.., row, column, band])
+                    .into_iter()
+                    .filter(|value| !value.equivalent(&nodata))
+                    .copied();
+                aggregate_series(series, aggregation).unwrap_or(nodata)
+            })
+            .collect();
+
+        self.geometry.with_data(
+            Array3::from_shape_vec((height, width, bands), values)
+                .expect("aggregated values match the grid shape"),
+            nodata,
+        )
+    }
+}
+
+impl<T: CellValue> GridCube<T> {
+    /// Interpolate every band of the cube to `timestamp`.
+    ///
+    /// A cell is nodata if it is nodata in either of the steps before and after `timestamp`.
+    /// With `TemporalInterpolation::Cubic`, the slope at a step falls back to a one-sided
+    /// estimate where the outer neighboring step is missing or nodata.
+    /// The step itself is returned if `timestamp` matches one.
+    ///
+    /// # Errors
+    /// Returns an error if `timestamp` is outside of the cube's time range.
+    pub fn interpolate_time(
+        &self,
+        timestamp: Timestamp,
+        method: TemporalInterpolation,
+    ) -> Result<Grid<T>, Box<dyn Error>> {
+        let after = self.timestamps.partition_point(|&step| step < timestamp);
+        if after == self.steps() || (after == 0 && self.timestamps[0] != timestamp) {
+            return Err("Timestamp is outside of the cube".into());
+        }
+        if self.timestamps[after] == timestamp {
+            return Ok(self.step(after));
+        }
+        let before = after - 1;
+
+        let (height, width, bands) = (self.height(), self.width(), self.bands());
+        let nodata = self.nodata();
+        let values: Vec<T> = (0..height * width * bands)
+            .into_par_iter()
+            .map(|idx| {
+                let (row, column, band) = (idx / (width * bands), idx / bands % width, idx % bands);
+                let known = |step: usize| {
+                    let value = *self.data.get([step, row, column, band])?;
+                    (!value.equivalent(&nodata)).then(|| (self.timestamps[step], value.to_f64()))
+                };
+                let (Some(start), Some(end)) = (known(before), known(after)) else {
+                    return nodata;
+                };
+                let interpolated = match method {
+                    TemporalInterpolation::Linear => linear(start, end, timestamp),
+                    TemporalInterpolation::Cubic => cubic_hermite(
+                        before.checked_sub(1).and_then(known),
+                        start,
+                        end,
+                        known(after + 1),
+                        timestamp,
+                    ),
+                };
+                T::from_f64(interpolated)
+            })
+            .collect();
+
+        Ok(self.geometry.with_data(
+            Array3::from_shape_vec((height, width, bands), values)
+                .expect("interpolated values match the grid shape"),
+            nodata,
+        ))
+    }
+
+    /// Interpolate every band of the cube to each of `timestamps`.
+    ///
+    /// # Errors
+    /// Returns an error if the timestamps are not strictly increasing
+    /// or any is outside of the cube's time range.
+    pub fn interpolate_times(
+        &self,
+        timestamps: &[Timestamp],
+        method: TemporalInterpolation,
+    ) -> Result<GridCube<T>, Box<dyn Error>> {
+        check_increasing(timestamps)?;
+        let mut cube =
+            GridCube::empty_from_grid(&self.geometry, timestamps.to_vec(), self.bands())?;
+        for (step, &timestamp) in timestamps.iter().enumerate() {
+            let grid = self.interpolate_time(timestamp, method)?;
+            cube.data.index_axis_mut(Axis(0), step).assign(&grid.data);
+        }
+        Ok(cube)
+    }
+}
+
+/// Interpolate linearly between two (time, value) pairs.
+fn linear(start: (Timestamp, f64), end: (Timestamp, f64), timestamp: Timestamp) -> f64 {
+    let fraction = (timestamp - start.0) as f64 / (end.0 - start.0) as f64;
+    start.1 + (end.1 - start.1) * fraction
+}
+
+/// Interpolate between two (time, value) pairs with a cubic Hermite spline,
+/// estimating the slope at each from its neighbors on either side where they are known.
+fn cubic_hermite(
+    previous: Option<(Timestamp, f64)>,
+    start: (Timestamp, f64),
+    end: (Timestamp, f64),
+    next: Option<(Timestamp, f64)>,
+    timestamp: Timestamp,
+) -> f64 {
+    let slope =
+        |from: (Timestamp, f64), to: (Timestamp, f64)| (to.1 - from.1) / (to.0 - from.0) as f64;
+    let start_slope = slope(previous.unwrap_or(start), end);
+    let end_slope = slope(start, next.unwrap_or(end));
+
+    let span = (end.0 - start.0) as f64;
+    let s = (timestamp - start.0) as f64 / span;
+    let (s2, s3) = (s * s, s * s * s);
+    (2. * s3 - 3. * s2 + 1.) * start.1
+        + (s3 - 2. * s2 + s) * span * start_slope
+        + (-2. * s3 + 3. * s2) * end.1
+        + (s3 - s2) * span * end_slope
+}
+
+/// Aggregate a series of values, or return None if it is empty.
+fn aggregate_series<T: CellValue>(
+    mut series: impl Iterator<Item = T>,
+    aggregation: TemporalAggregation,
+) -> Option<T> {
+    let first = series.next()?;
+    Some(match aggregation {
+        TemporalAggregation::Min => {
+            series.fold(first, |min, value| if value < min { value } else { min })
+        }
+        TemporalAggregation::Max => {
+            series.fold(first, |max, value| if value > max { value } else { max })
+        }
+        TemporalAggregation::Sum | TemporalAggregation::Mean => {
+            let (sum, count) = series.fold((first.to_f64(), 1), |(sum, count), value| {
+                (sum + value.to_f64(), count + 1)
+            });
+            if aggregation == TemporalAggregation::Sum {
+                T::from_f64(sum)
+            } else {
+                T::from_f64(sum / f64::from(count))
+            }
+        }
+    })
+}
+
+fn check_increasing(timestamps: &[Timestamp]) -> Result<(), Box<dyn Error>> {
+    if timestamps.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err("Timestamps must be strictly increasing".into());
+    }
+    Ok(())
+}
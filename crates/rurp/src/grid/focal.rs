@@ -0,0 +1,173 @@
+use std::error::Error;
+
+use crate::cell_value::CellValue;
+use crate::grid::Grid;
+use ndarray::prelude::*;
+use rayon::prelude::*;
+
+/// The statistic computed over the window around each cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FocalOperation {
+    /// The mean of the cells in the window.
+    Mean,
+    /// The mean of the cells in the window weighted by a Gaussian of their distance from the
+    /// center, with a standard deviation of `sigma` cells.
+    Gaussian { sigma: f64 },
+    /// The median of the cells in the window, the mean of the middle two for an even count.
+    Median,
+    /// The smallest cell in the window.
+    Min,
+    /// The largest cell in the window.
+    Max,
+}
+
+impl<T: CellValue> Grid<T> {
+    /// Apply a focal operation to every band over a square window of `radius` cells on each side
+    /// of each cell, returning a new grid with the same geometry.
+    ///
+    /// Nodata cells and cells outside of the grid are left out of each window, with the weights
+    /// of the others renormalized. Nodata cells stay nodata in the result.
+    ///
+    /// # Arguments
+    /// `operation` - The statistic to compute over each window.
+    /// `radius` - The number of cells on each side of the center of the window.
+    ///
+    /// # Errors
+    /// Returns an error if the Gaussian sigma is not positive and finite.
+    pub fn focal(
+        &self,
+        operation: FocalOperation,
+        radius: usize,
+    ) -> Result<Grid<T>, Box<dyn Error>> {
+        let size = 2 * radius + 1;
+        match operation {
+            FocalOperation::Mean => {
+                self.convolve(&Array2::from_elem((size, size), 1. / (size * size) as f64))
+            }
+            FocalOperation::Gaussian { sigma } => {
+                if !(sigma.is_finite() && sigma > 0.) {
+                    return Err("Sigma must be positive and finite".into());
+                }
+                let kernel = Array2::from_shape_fn((size, size), |(i, j)| {
+                    let (dy, dx) = (i as f64 - radius as f64, j as f64 - radius as f64);
+                    (-(dx.powi(2) + dy.powi(2)) / (2. * sigma.powi(2))).exp()
+                });
+                let total_weight = kernel.sum();
+                self.convolve(&(kernel / total_weight))
+            }
+            FocalOperation::Median => Ok(self.focal_map((radius, radius), |window| {
+                let mut values: Vec<f64> = window.iter().map(|(value, _)| *value).collect();
+                values.sort_by(f64::total_cmp);
+                let middle = values.len() / 2;
+                if values.len().is_multiple_of(2) {
+                    Some((values[middle - 1] + values[middle]) / 2.)
+                } else {
+                    Some(values[middle])
+                }
+            })),
+            FocalOperation::Min => Ok(self.focal_map((radius, radius), |window| {
+                window.iter().map(|(value, _)| *value).reduce(f64::min)
+            })),
+            FocalOperation::Max => Ok(self.focal_map((radius, radius), |window| {
+                window.iter().map(|(value, _)| *value).reduce(f64::max)
+            })),
+        }
+    }
+
+    /// Convolve every band with a kernel centered on each cell, returning a new grid with the
+    /// same geometry.
+    ///
+    /// Kernel rows follow the grid rows, from the bottom. The kernel is not flipped, so this is a
+    /// correlation; flip both axes of the kernel for a true convolution.
+    ///
+    /// Where nodata cells or cells outside of the grid fall under a kernel whose weights have a
+    /// non-zero sum, the result is scaled by that sum over the sum of the weights of the cells
+    /// present, so a kernel whose weights sum to one yields the weighted mean of the cells present.
+    /// Kernels whose weights sum to zero, such as edge detectors, cannot be rescaled, so cells where
+    /// any cell with a non-zero weight is missing are nodata. Nodata cells stay nodata in the result.
+    ///
+    /// # Errors
+    /// Returns an error if the kernel does not have odd dimensions or all of its weights are zero.
+    pub fn convolve(&self, kernel: &Array2<f64>) -> Result<Grid<T>, Box<dyn Error>> {
+        let (kernel_height, kernel_width) = kernel.dim();
+        if kernel_height.is_multiple_of(2) || kernel_width.is_multiple_of(2) {
+            return Err("Kernel must have odd dimensions".into());
+        }
+        let total_magnitude: f64 = kernel.iter().map(|weight| weight.abs()).sum();
+        if total_magnitude.is_nan() || total_magnitude <= 0. {
+            return Err("Kernel weights must not all be zero".into());
+        }
+
+        let weights: Vec<f64> = kernel.iter().copied().collect();
+        let weight_count = weights.iter().filter(|weight| **weight != 0.).count();
+        let total_weight: f64 = weights.iter().sum();
+        // Sums within rounding error of zero, as of kernels with fractional weights, count as zero.
+        let is_zero_sum = total_weight.abs() <= 1e-12 * total_magnitude;
+
+        Ok(
+            self.focal_map((kernel_height / 2, kernel_width / 2), |window| {
+                let (sum, present_weight, present_count) = window.iter().fold(
+                    (0., 0., 0),
+                    |(sum, present_weight, present_count), &(value, idx)| {
+                        (
+                            sum + weights[idx] * value,
+                            present_weight + weights[idx],
+                            present_count + usize::from(weights[idx] != 0.),
+                        )
+                    },
+                );
+                if is_zero_sum {
+                    (present_count == weight_count).then_some(sum)
+                } else {
+                    (present_weight != 0.).then(|| sum * total_weight / present_weight)
+                }
+            }),
+        )
+    }
+
+    /// Reduce the window of `radius` rows and columns around every cell of every band.
+    ///
+    /// The window holds the value of each cell with data together with its index in a row major
+    /// kernel of the window size. Nodata cells and windows reduced to None are nodata in the result.
+    fn focal_map<F>(&self, (row_radius, column_radius): (usize, usize), reduce: F) -> Grid<T>
+    where
+        F: Fn(&[(f64, usize)]) -> Option<f64> + Sync,
+    {
+        let (height, width, bands) = (self.height, self.width, self.bands());
+        let nodata = self.nodata;
+        let kernel_width = 2 * column_radius + 1;
+
+        let values: Vec<T> = (0..height * width * bands)
+            .into_par_iter()
+            .map_init(Vec::new, |window, idx| {
+                let (row, column, band) = (idx / (width * bands), idx / bands % width, idx % bands);
+                if self.data[[row, column, band]].equivalent(&nodata) {
+                    return nodata;
+                }
+
+                window.clear();
+                let rows = row.saturating_sub(row_radius)..(row + row_radius + 1).min(height);
+                for neighbor_row in rows {
+                    let columns = column.saturating_sub(column_radius)
+                        ..(column + column_radius + 1).min(width);
+                    for neighbor_column in columns {
+                        let value = self.data[[neighbor_row, neighbor_column, band]];
+                        if value.equivalent(&nodata) {
+                            continue;
+                        }
+                        let kernel_row = neighbor_row + row_radius - row;
+                        let kernel_column = neighbor_column + column_radius - column;
+                        window.push((value.to_f64(), kernel_row * kernel_width + kernel_column));
+                    }
+                }
+                reduce(window).map_or(nodata, T::from_f64)
+            })
+            .collect();
+
+        self.with_data(
+            Array3::from_shape_vec((height, width, bands), values)
+                .expect("filtered values match the grid shape"),
+            nodata,
+        )
+    }
+}
@@ -0,0 +1,96 @@
+use std::error::Error;
+
+use crate::cell_value::CellValue;
+use crate::grid::Grid;
+use geo::{MultiPolygon, Polygon};
+use ndarray::prelude::*;
+
+/// The cells of a grid that masking sets to nodata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskRegion {
+    /// Mask the cells outside of the mask, keeping those inside.
+    Outside,
+    /// Mask the cells inside of the mask, keeping those outside.
+    Inside,
+}
+
+impl<T: CellValue> Grid<T> {
+    /// Set the cells inside or outside of polygons to nodata, in every band.
+    /// The polygons are assumed to be in world space.
+    ///
+    /// A cell is inside if it is inside any polygon, using the same cell selection as `rasterize_polygons`.
+    ///
+    /// # Errors
+    /// Returns an error if the rasterization fails.
+    pub fn mask_polygons(
+        &mut self,
+        polygons: &[Polygon<f64>],
+        region: MaskRegion,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut rasterizer = self.build_rasterizer(0u8)?;
+        for polygon in polygons {
+            rasterizer.rasterize(polygon, 1)?;
+        }
+        self.mask_cells(&rasterizer.finish().mapv(|label| label > 0), region);
+        Ok(())
+    }
+
+    /// Set the cells inside or outside of multi polygons to nodata, in every band.
+    /// The multi polygons are assumed to be in world space.
+    ///
+    /// # Errors
+    /// Returns an error if the rasterization fails.
+    pub fn mask_multi_polygons(
+        &mut self,
+        multi_polygons: &[MultiPolygon<f64>],
+        region: MaskRegion,
+    ) -> Result<(), Box<dyn Error>> {
+        let polygons: Vec<Polygon<f64>> = multi_polygons
+            .iter()
+            .flat_map(|multi_polygon| multi_polygon.0.iter().cloned())
+            .collect();
+        self.mask_polygons(&polygons, region)
+    }
+
+    /// Set the cells inside or outside of a mask grid to nodata, in every band.
+    ///
+    /// A cell is inside the mask if the first band of the mask is neither nodata nor zero there,
+    /// so a land-sea mask with land as one keeps land cells with `MaskRegion::Outside`.
+    ///
+    /// # Errors
+    /// Returns an error if the mask does not have the same geometry as the grid or has no bands.
+    pub fn mask_by_grid<U: CellValue>(
+        &mut self,
+        mask: &Grid<U>,
+        region: MaskRegion,
+    ) -> Result<(), Box<dyn Error>> {
+        if !self.same_geometry(mask) {
+            return Err("Mask must have the same geometry as the grid".into());
+        }
+        if mask.bands() == 0 {
+            return Err("Mask must have at least one band".into());
+        }
+        let zero = U::from_f64(0.);
+        let inside = mask
+            .data
+            .index_axis(Axis(2), 0)
+            .mapv(|value| !value.equivalent(&mask.nodata) && !value.equivalent(&zero));
+        self.mask_cells(&inside, region);
+        Ok(())
+    }
+
+    /// Set the cells in `region` relative to `inside` to nodata, in every band.
+    fn mask_cells(&mut self, inside: &Array2<bool>, region: MaskRegion) {
+        let nodata = self.nodata;
+        let masked = match region {
+            MaskRegion::Outside => false,
+            MaskRegion::Inside => true,
+        };
+        self.data
+            .lanes_mut(Axis(2))
+            .into_iter()
+            .zip(inside)
+            .filter(|(_, &inside)| inside == masked)
+            .for_each(|(mut cell, _)| cell.fill(nodata));
+    }
+}
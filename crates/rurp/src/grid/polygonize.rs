@@ -0,0 +1,140 @@
+use std::error::Error;
+
+use crate::cell_value::CellValue;
+use crate::grid::Grid;
+use crate::rings::{boundary_edges, polygons_from_edges};
+use euclid::Point2D;
+use geo::orient::{Direction, Orient};
+use geo::{Coord, MultiPolygon};
+use ndarray::prelude::*;
+
+/// Which neighboring cells are connected into one region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Connectivity {
+    /// Cells sharing a side.
+    #[default]
+    Four,
+    /// Cells sharing a side or a corner.
+    Eight,
+}
+
+impl Connectivity {
+    /// Get the row and column offsets of the neighbors of a cell.
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+            Connectivity::Eight => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// A region of connected cells with one value, as that value and the polygons covering the cells.
+pub type Region<T> = (T, MultiPolygon<f64>);
+
+impl<T: CellValue> Grid<T> {
+    /// Convert the connected regions of cells with equal values in a band into polygons,
+    /// the inverse of `rasterize_polygons`. The polygons are in world space.
+    ///
+    /// Each region is returned with its value, in the order of its first cell from the bottom row up.
+    /// Its polygons follow the cell edges, with counterclockwise exteriors and clockwise holes.
+    /// A region is a single polygon with four connectivity, and may be several polygons that touch
+    /// at corners with eight connectivity. Nodata cells are not part of any region.
+    ///
+    /// # Arguments
+    /// `band` - The band to polygonize.
+    /// `connectivity` - Which neighboring cells with equal values are in the same region.
+    ///
+    /// # Errors
+    /// Returns an error if the band is out of range.
+    pub fn polygonize(
+        &self,
+        band: usize,
+        connectivity: Connectivity,
+    ) -> Result<Vec<Region<T>>, Box<dyn Error>> {
+        if band >= self.bands() {
+            return Err("Band is out of range".into());
+        }
+
+        let values = self.data.index_axis(Axis(2), band);
+        let regions = connected_regions(&values, self.nodata, connectivity);
+
+        Ok(regions
+            .into_iter()
+            .map(|cells| {
+                let (row, column) = cells[0];
+                let pieces = cells.into_iter().map(|(row, column)| {
+                    vec![
+                        (row, column),
+                        (row, column + 1),
+                        (row + 1, column + 1),
+                        (row + 1, column),
+                    ]
+                });
+                let edges = boundary_edges(pieces);
+                let polygons = polygons_from_edges(&edges, |(row, column)| {
+                    let point = self
+                        .screen_to_world_transform
+                        .transform_point(Point2D::new(column as f64, row as f64));
+                    Coord {
+                        x: point.x,
+                        y: point.y,
+                    }
+                });
+                (values[[row, column]], polygons.orient(Direction::Default))
+            })
+            .collect())
+    }
+}
+
+/// Group the cells with data into regions of connected cells with equivalent values.
+///
+/// Regions are ordered by their first cell in row major order, which is also the first cell of each region.
+fn connected_regions<T: CellValue>(
+    values: &ArrayView2<'_, T>,
+    nodata: T,
+    connectivity: Connectivity,
+) -> Vec<Vec<(usize, usize)>> {
+    let (height, width) = values.dim();
+    let mut visited = Array2::from_elem((height, width), false);
+    let mut regions = vec![];
+
+    for ((row, column), value) in values.indexed_iter() {
+        if visited[[row, column]] || value.equivalent(&nodata) {
+            continue;
+        }
+        visited[[row, column]] = true;
+        let mut region = vec![(row, column)];
+        let mut stack = vec![(row, column)];
+        while let Some((row, column)) = stack.pop() {
+            for &(row_offset, column_offset) in connectivity.offsets() {
+                let (Some(neighbor_row), Some(neighbor_column)) = (
+                    row.checked_add_signed(row_offset),
+                    column.checked_add_signed(column_offset),
+                ) else {
+                    continue;
+                };
+                if neighbor_row >= height
+                    || neighbor_column >= width
+                    || visited[[neighbor_row, neighbor_column]]
+                    || !values[[neighbor_row, neighbor_column]].equivalent(value)
+                {
+                    continue;
+                }
+                visited[[neighbor_row, neighbor_column]] = true;
+                region.push((neighbor_row, neighbor_column));
+                stack.push((neighbor_row, neighbor_column));
+            }
+        }
+        regions.push(region);
+    }
+    regions
+}
@@ -0,0 +1,451 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::ops::Range;
+
+use crate::cell_value::CellValue;
+use crate::grid::Grid;
+use euclid::{Point2D, Vector2D};
+use geo::{BoundingRect, Geometry, LineString, Polygon};
+use geo_rasterize::LabelBuilder;
+use ndarray::prelude::*;
+
+/// How a burned label is combined with the value already in a cell.
+///
+/// Nodata cells always take the label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeMode {
+    /// Replace the value with the label.
+    #[default]
+    Replace,
+    /// Add the label to the value.
+    Add,
+    /// Keep the larger of the value and the label.
+    Max,
+    /// Keep the smaller of the value and the label.
+    Min,
+}
+
+/// Options for rasterizing geometries onto a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RasterizeOptions {
+    /// How labels are combined with the values already in the grid.
+    pub merge: MergeMode,
+    /// Burn every cell touched by a polygonal geometry,
+    /// instead of only the cells whose centers are inside it.
+    /// Points and lines always burn every cell they touch.
+    pub all_touched: bool,
+    /// The band to burn into.
+    pub band: usize,
+    /// Burn every cell a polygonal geometry overlaps with its label scaled by
+    /// the fraction of the cell it covers, for area-weighted work such as
+    /// summing with `MergeMode::Add`. Overrides `all_touched`.
+    pub coverage_fraction: bool,
+}
+
+/// A window of cells, as row and column ranges.
+pub(super) type Window = (Range<usize>, Range<usize>);
+
+/// A window of cells with a weight per cell.
+pub(super) type WeightedWindow = (Window, Array2<f64>);
+
+/// A ring in screen space.
+type ScreenRing = Vec<(f64, f64)>;
+
+/// A signed distance of a point inside of a bound, negative outside.
+type InsideDistance = fn((f64, f64), f64) -> f64;
+
+impl<T: CellValue> Grid<T> {
+    /// Rasterize geometries onto a band of the grid.
+    /// The geometries are assumed to be in world space.
+    ///
+    /// Geometries are burned in order, each into the cells it covers, and merged with
+    /// the existing values according to `options`. Other cells and bands are left unchanged.
+    /// A geometry collection is burned once into the cells covered by any of its members.
+    ///
+    /// # Errors
+    /// Returns an error if there is not one label per geometry, the band is out of range,
+    /// coverage fractions are requested for a geometry that is not polygonal,
+    /// or the rasterization fails.
+    pub fn rasterize_geometries(
+        &mut self,
+        geometries: &[Geometry<f64>],
+        labels: &[T],
+        options: RasterizeOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        if geometries.len() != labels.len() {
+            return Err("There must be one label per geometry".into());
+        }
+        if options.band >= self.bands() {
+            return Err("Band is out of range".into());
+        }
+
+        for (geometry, &label) in geometries.iter().zip(labels) {
+            let Some((window, fractions)) =
+                self.cell_weights(geometry, options.all_touched, options.coverage_fraction)?
+            else {
+                continue;
+            };
+
+            let nodata = self.nodata;
+            let mut cells =
+                self.data
+                    .slice_mut(s![window.0.clone(), window.1.clone(), options.band]);
+            cells
+                .iter_mut()
+                .zip(&fractions)
+                .filter(|(_, &fraction)| fraction > 0.)
+                .for_each(|(cell, &fraction)| {
+                    let label = if options.coverage_fraction {
+                        T::from_f64(label.to_f64() * fraction)
+                    } else {
+                        label
+                    };
+                    *cell = merge(*cell, label, nodata, options.merge);
+                });
+        }
+        Ok(())
+    }
+
+    /// Get the fraction of each cell covered by a polygonal geometry, with the shape of the grid.
+    /// The geometry is assumed to be in world space.
+    ///
+    /// # Errors
+    /// Returns an error if the geometry is not polygonal.
+    pub fn coverage_fractions(
+        &self,
+        geometry: &Geometry<f64>,
+    ) -> Result<Array2<f64>, Box<dyn Error>> {
+        let polygons =
+            polygons_of(geometry).ok_or("Coverage fractions require polygonal geometries")?;
+        let mut fractions = Array2::zeros((self.height, self.width));
+        if let Some(window) = self.screen_window(geometry) {
+            fractions
+                .slice_mut(s![window.0.clone(), window.1.clone()])
+                .assign(&self.fraction_coverage(&polygons, &window));
+        }
+        Ok(fractions)
+    }
+
+    /// Get the weight of each cell of the window around a geometry, or None if the window is empty.
+    ///
+    /// Weights are the covered fractions of the cells with `coverage_fraction`,
+    /// and otherwise one for covered cells and zero for the others.
+    ///
+    /// # Errors
+    /// Returns an error if coverage fractions are requested for a geometry that is not polygonal,
+    /// or the rasterization fails.
+    pub(super) fn cell_weights(
+        &self,
+        geometry: &Geometry<f64>,
+        all_touched: bool,
+        coverage_fraction: bool,
+    ) -> Result<Option<WeightedWindow>, Box<dyn Error>> {
+        let polygons = if coverage_fraction {
+            Some(polygons_of(geometry).ok_or("Coverage fractions require polygonal geometries")?)
+        } else {
+            None
+        };
+        let Some(window) = self.screen_window(geometry) else {
+            return Ok(None);
+        };
+        let weights = match polygons {
+            Some(polygons) => self.fraction_coverage(&polygons, &window),
+            None => self
+                .coverage(geometry, &window, all_touched)?
+                .mapv(|covered| if covered { 1. } else { 0. }),
+        };
+        Ok(Some((window, weights)))
+    }
+
+    /// Get the cells overlapping the bounding box of a geometry, or None if there are none.
+    fn screen_window(&self, geometry: &Geometry<f64>) -> Option<Window> {
+        let rect = geometry.bounding_rect()?;
+        let corners = [rect.min(), rect.max()].map(|corner| {
+            self.world_to_screen_transform
+                .transform_point(Point2D::new(corner.x, corner.y))
+        });
+        let (x_min, x_max) = (
+            corners[0].x.min(corners[1].x),
+            corners[0].x.max(corners[1].x),
+        );
+        let (y_min, y_max) = (
+            corners[0].y.min(corners[1].y),
+            corners[0].y.max(corners[1].y),
+        );
+
+        let cells = |min: f64, max: f64, count: usize| {
+            let start = min.floor().max(0.);
+            let end = (max.floor() + 1.).min(count as f64);
+            (start < end).then_some(start as usize..end as usize)
+        };
+        Some((
+            cells(y_min, y_max, self.height)?,
+            cells(x_min, x_max, self.width)?,
+        ))
+    }
+
+    /// Get which cells of a window a geometry covers.
+    fn coverage(
+        &self,
+        geometry: &Geometry<f64>,
+        window: &Window,
+        all_touched: bool,
+    ) -> Result<Array2<bool>, Box<dyn Error>> {
+        let polygons = match geometry {
+            Geometry::GeometryCollection(_) => None,
+            _ => polygons_of(geometry),
+        };
+
+        match (geometry, polygons) {
+            (Geometry::GeometryCollection(collection), _) => {
+                let mut coverage = Array2::from_elem((window.0.len(), window.1.len()), false);
+                for member in collection {
+                    let member_coverage = self.coverage(member, window, all_touched)?;
+                    coverage.zip_mut_with(&member_coverage, |covered, &member_covered| {
+                        *covered |= member_covered;
+                    });
+                }
+                Ok(coverage)
+            }
+            (_, Some(polygons)) if !all_touched => Ok(self.center_coverage(&polygons, window)),
+            _ => self.touched_coverage(geometry, window),
+        }
+    }
+
+    /// Get which cells of a window a geometry touches.
+    fn touched_coverage(
+        &self,
+        geometry: &Geometry<f64>,
+        (rows, columns): &Window,
+    ) -> Result<Array2<bool>, Box<dyn Error>> {
+        let geo_pix_transform = self
+            .world_to_screen_transform
+            .then_translate(Vector2D::new(-(columns.start as f64), -(rows.start as f64)))
+            .to_untyped();
+        let mut rasterizer = LabelBuilder::background(0u8)
+            .width(columns.len())
+            .height(rows.len())
+            .geo_to_pix(geo_pix_transform)
+            .build()?;
+        rasterizer.rasterize(geometry, 1)?;
+        Ok(rasterizer.finish().mapv(|label| label > 0))
+    }
+
+    /// Get which cells of a window have their centers inside any of the polygons.
+    ///
+    /// Each polygon is scanned along the row centers, filling between pairs of ring crossings.
+    fn center_coverage(&self, polygons: &[Polygon<f64>], (rows, columns): &Window) -> Array2<bool> {
+        let mut coverage = Array2::from_elem((rows.len(), columns.len()), false);
+        let mut crossings = vec![];
+        for polygon in polygons {
+            let edges: Vec<_> = polygon
+                .exterior()
+                .lines()
+                .chain(polygon.interiors().iter().flat_map(|ring| ring.lines()))
+                .map(|line| {
+                    [line.start, line.end].map(|coord| {
+                        self.world_to_screen_transform
+                            .transform_point(Point2D::new(coord.x, coord.y))
+                    })
+                })
+                .collect();
+
+            for (i, row) in rows.clone().enumerate() {
+                let center_y = row as f64 + 0.5;
+                crossings.clear();
+                crossings.extend(
+                    edges
+                        .iter()
+                        .filter(|[start, end]| (start.y <= center_y) != (end.y <= center_y))
+                        .map(|[start, end]| {
+                            start.x + (center_y - start.y) * (end.x - start.x) / (end.y - start.y)
+                        }),
+                );
+                crossings.sort_by(f64::total_cmp);
+
+                for pair in crossings.chunks_exact(2) {
+                    // Cells whose centers `column + 0.5` are in [pair[0], pair[1]).
+                    let first = ((pair[0] - 0.5).ceil().max(columns.start as f64)) as usize;
+                    let last = ((pair[1] - 0.5).ceil().min(columns.end as f64)).max(0.) as usize;
+                    for column in first..last.max(first) {
+                        coverage[[i, column - columns.start]] = true;
+                    }
+                }
+            }
+        }
+        coverage
+    }
+}
+
+impl<T: CellValue> Grid<T> {
+    /// Get the fraction of each cell of a window covered by the polygons.
+    ///
+    /// Cells crossed by a ring are clipped exactly; other cells are fully inside or outside,
+    /// which is decided by their centers.
+    fn fraction_coverage(&self, polygons: &[Polygon<f64>], window: &Window) -> Array2<f64> {
+        let (rows, columns) = window;
+        let mut fractions =
+            self.center_coverage(polygons, window)
+                .mapv(|covered| if covered { 1. } else { 0. });
+
+        let to_screen = |ring: &LineString<f64>| -> ScreenRing {
+            ring.coords()
+                .map(|coord| {
+                    let point = self
+                        .world_to_screen_transform
+                        .transform_point(Point2D::new(coord.x, coord.y));
+                    (point.x, point.y)
+                })
+                .collect()
+        };
+        let screen_polygons: Vec<(ScreenRing, Vec<ScreenRing>)> = polygons
+            .iter()
+            .map(|polygon| {
+                (
+                    to_screen(polygon.exterior()),
+                    polygon.interiors().iter().map(to_screen).collect(),
+                )
+            })
+            .collect();
+
+        let mut boundary_cells = HashSet::new();
+        for (exterior, interiors) in &screen_polygons {
+            for ring in std::iter::once(exterior).chain(interiors) {
+                ring.windows(2)
+                    .for_each(|edge| crossed_cells(edge[0], edge[1], window, &mut boundary_cells));
+            }
+        }
+
+        for (row, column) in boundary_cells {
+            let cell = [
+                column as f64,
+                row as f64,
+                (column + 1) as f64,
+                (row + 1) as f64,
+            ];
+            let area: f64 = screen_polygons
+                .iter()
+                .map(|(exterior, interiors)| {
+                    clipped_area(exterior, cell)
+                        - interiors
+                            .iter()
+                            .map(|interior| clipped_area(interior, cell))
+                            .sum::<f64>()
+                })
+                .sum();
+            fractions[[row - rows.start, column - columns.start]] = area.clamp(0., 1.);
+        }
+        fractions
+    }
+}
+
+/// Get the polygons of a polygonal geometry, or None if it is not polygonal.
+fn polygons_of(geometry: &Geometry<f64>) -> Option<Vec<Polygon<f64>>> {
+    match geometry {
+        Geometry::Polygon(polygon) => Some(vec![polygon.clone()]),
+        Geometry::MultiPolygon(multi_polygon) => Some(multi_polygon.0.clone()),
+        Geometry::Rect(rect) => Some(vec![rect.to_polygon()]),
+        Geometry::Triangle(triangle) => Some(vec![triangle.to_polygon()]),
+        Geometry::GeometryCollection(collection) => collection
+            .iter()
+            .map(polygons_of)
+            .collect::<Option<Vec<_>>>()
+            .map(|polygons| polygons.concat()),
+        _ => None,
+    }
+}
+
+/// Add the cells of a window crossed by a screen space edge to `cells`.
+///
+/// Every row the edge spans is visited, adding the columns the edge spans within that row.
+fn crossed_cells(
+    start: (f64, f64),
+    end: (f64, f64),
+    (rows, columns): &Window,
+    cells: &mut HashSet<(usize, usize)>,
+) {
+    let (y_min, y_max) = (start.1.min(end.1), start.1.max(end.1));
+    let first_row = y_min.floor().max(rows.start as f64);
+    let last_row = y_max.floor().min(rows.end as f64 - 1.);
+    if first_row > last_row {
+        return;
+    }
+    for row in first_row as usize..=last_row as usize {
+        let (x_start, x_end) = if end.1 == start.1 {
+            (start.0, end.0)
+        } else {
+            let x_at = |y: f64| start.0 + (y - start.1) * (end.0 - start.0) / (end.1 - start.1);
+            (
+                x_at(y_min.max(row as f64)),
+                x_at(y_max.min((row + 1) as f64)),
+            )
+        };
+        let first_column = x_start.min(x_end).floor().max(columns.start as f64);
+        let last_column = x_start.max(x_end).floor().min(columns.end as f64 - 1.);
+        if first_column > last_column {
+            continue;
+        }
+        for column in first_column as usize..=last_column as usize {
+            cells.insert((row, column));
+        }
+    }
+}
+
+/// Get the area of a ring clipped to a cell `[left, bottom, right, top]`,
+/// using Sutherland–Hodgman clipping against each cell edge in turn.
+fn clipped_area(ring: &[(f64, f64)], [left, bottom, right, top]: [f64; 4]) -> f64 {
+    let mut polygon: Vec<(f64, f64)> = ring.to_vec();
+    polygon.pop();
+    let clip_edges: [(InsideDistance, f64); 4] = [
+        (|point, bound| point.0 - bound, left),
+        (|point, bound| bound - point.0, right),
+        (|point, bound| point.1 - bound, bottom),
+        (|point, bound| bound - point.1, top),
+    ];
+    for (inside_distance, bound) in clip_edges {
+        if polygon.is_empty() {
+            return 0.;
+        }
+        let mut clipped = Vec::with_capacity(polygon.len() + 4);
+        for (idx, &current) in polygon.iter().enumerate() {
+            let previous = polygon[(idx + polygon.len() - 1) % polygon.len()];
+            let (current_distance, previous_distance) = (
+                inside_distance(current, bound),
+                inside_distance(previous, bound),
+            );
+            if (current_distance >= 0.) != (previous_distance >= 0.) {
+                let t = previous_distance / (previous_distance - current_distance);
+                clipped.push((
+                    previous.0 + t * (current.0 - previous.0),
+                    previous.1 + t * (current.1 - previous.1),
+                ));
+            }
+            if current_distance >= 0. {
+                clipped.push(current);
+            }
+        }
+        polygon = clipped;
+    }
+
+    let twice_area: f64 = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum();
+    twice_area.abs() / 2.
+}
+
+/// Combine a burned label with the value in a cell.
+fn merge<T: CellValue>(value: T, label: T, nodata: T, mode: MergeMode) -> T {
+    if value.equivalent(&nodata) {
+        return label;
+    }
+    match mode {
+        MergeMode::Replace => label,
+        // Add in f64 so integer cells saturate at their limits instead of overflowing.
+        MergeMode::Add => T::from_f64(value.to_f64() + label.to_f64()),
+        MergeMode::Max if label > value => label,
+        MergeMode::Min if label < value => label,
+        MergeMode::Max | MergeMode::Min => value,
+    }
+}
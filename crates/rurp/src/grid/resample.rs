@@ -0,0 +1,151 @@
+use std::error::Error;
+
+use crate::bounds::Bounds;
+use crate::equivalent;
+use crate::grid::sample::SampleMethod;
+use crate::grid::Grid;
+use crate::resolution::{ExtentPolicy, Resolution};
+use euclid::Point2D;
+use ndarray::prelude::*;
+use rayon::prelude::*;
+
+/// The kernel used to resample a grid into a new geometry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResampleMethod {
+    /// The value of the source cell containing the target cell center.
+    Nearest,
+    /// Linear interpolation of the source at the target cell center.
+    Bilinear,
+    /// Cubic convolution of the source at the target cell center.
+    Cubic,
+    /// The mean of the source cells overlapping the target cell, weighted by overlap area.
+    /// Nodata source cells are ignored. Suited to coarsening.
+    Average,
+}
+
+impl Grid {
+    /// Resample every band of the grid into the geometry of `target`.
+    ///
+    /// The target data is replaced with one band per source band.
+    /// Target cells that cannot be resampled, because they are outside of the source
+    /// or their source cells are nodata, are set to the target nodata value.
+    pub fn resample_into(&self, target: &mut Grid, method: ResampleMethod) {
+        let bands = self.bands();
+        let (height, width) = (target.height, target.width);
+        let target_nodata = target.nodata;
+        let screen_to_world = target.screen_to_world_transform;
+
+        let values: Vec<f64> = (0..height * width)
+            .into_par_iter()
+            .flat_map_iter(|idx| {
+                let (row, column) = (idx / width, idx % width);
+                (0..bands).map(move |band| {
+                    let value = match method {
+                        ResampleMethod::Average => {
+                            let lower = screen_to_world
+                                .transform_point(Point2D::new(column as f64, row as f64));
+                            let upper = screen_to_world.transform_point(Point2D::new(
+                                (column + 1) as f64,
+                                (row + 1) as f64,
+                            ));
+                            self.area_average(lower.x, lower.y, upper.x, upper.y, band)
+                        }
+                        _ => {
+                            let center = screen_to_world.transform_point(Point2D::new(
+                                column as f64 + 0.5,
+                                row as f64 + 0.5,
+                            ));
+                            let sample_method = match method {
+                                ResampleMethod::Nearest => SampleMethod::Nearest,
+                                ResampleMethod::Bilinear => SampleMethod::Bilinear,
+                                _ => SampleMethod::Bicubic,
+                            };
+                            self.sample(center.x, center.y, band, sample_method)
+                        }
+                    };
+                    if equivalent(&value, &self.nodata) {
+                        target_nodata
+                    } else {
+                        value
+                    }
+                })
+            })
+            .collect();
+
+        target.data = Array3::from_shape_vec((height, width, bands), values)
+            .expect("resampled values match the target shape");
+    }
+
+    /// Resample every band of the grid into a new grid with the given bounds and resolution.
+    ///
+    /// The new grid has the registration of this grid and is laid out as by `Grid::empty_from_resolution`.
+    ///
+    /// # Arguments
+    /// `bounds` - The bounds of the new grid.
+    /// `resolution` - The cell size of the new grid.
+    /// `policy` - How the bounds are fit if they are not a whole number of cells.
+    /// `method` - The kernel used to resample.
+    ///
+    /// # Errors
+    /// Returns an error if the bounds do not fit the resolution under `policy`
+    /// or the new grid cannot be created.
+    pub fn resample(
+        &self,
+        bounds: &Bounds,
+        resolution: &Resolution,
+        policy: ExtentPolicy,
+        method: ResampleMethod,
+    ) -> Result<Grid, Box<dyn Error>> {
+        let mut target = Grid::empty_from_resolution(
+            bounds,
+            resolution,
+            policy,
+            self.registration,
+            self.nodata,
+        )?;
+        self.resample_into(&mut target, method);
+        Ok(target)
+    }
+
+    /// Get the overlap-weighted mean of a band over a world space rectangle.
+    ///
+    /// Returns the nodata value if no valid cell overlaps the rectangle.
+    fn area_average(&self, left: f64, bottom: f64, right: f64, top: f64, band: usize) -> f64 {
+        let lower = self
+            .world_to_screen_transform
+            .transform_point(Point2D::new(left, bottom));
+        let upper = self
+            .world_to_screen_transform
+            .transform_point(Point2D::new(right, top));
+        let (x_min, x_max) = (lower.x.min(upper.x), lower.x.max(upper.x));
+        let (y_min, y_max) = (lower.y.min(upper.y), lower.y.max(upper.y));
+
+        let column_range =
+            x_min.floor().max(0.) as usize..(x_max.ceil().min(self.width as f64)) as usize;
+        let row_range =
+            y_min.floor().max(0.) as usize..(y_max.ceil().min(self.height as f64)) as usize;
+
+        let mut weighted_sum = 0.;
+        let mut weights_sum = 0.;
+        for row in row_range {
+            let row_overlap = (y_max.min((row + 1) as f64) - y_min.max(row as f64)).max(0.);
+            for column in column_range.clone() {
+                let column_overlap =
+                    (x_max.min((column + 1) as f64) - x_min.max(column as f64)).max(0.);
+                let weight = row_overlap * column_overlap;
+                let cell_value = self.data[[row, column, band]];
+                if weight == 0. || equivalent(&cell_value, &self.nodata) {
+                    continue;
+                }
+                weighted_sum += cell_value * weight;
+                weights_sum += weight;
+            }
+        }
+
+        if weights_sum == 0. {
+            self.nodata
+        } else {
+            weighted_sum / weights_sum
+        }
+    }
+}
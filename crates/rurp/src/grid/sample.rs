@@ -0,0 +1,104 @@
+use crate::equivalent;
+use crate::grid::Grid;
+use crate::point::Point;
+use euclid::Point2D;
+use rayon::prelude::*;
+
+/// The kernel used to read a grid at a location between cell centers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleMethod {
+    /// The value of the cell containing the location.
+    Nearest,
+    /// Linear interpolation between the 2x2 closest cell centers.
+    Bilinear,
+    /// Cubic convolution over the 4x4 closest cell centers.
+    Bicubic,
+}
+
+/// Get the weights of the cells along one axis for a screen space coordinate.
+///
+/// Indices are clamped to the grid, so edge cells are repeated outwards.
+fn axis_weights(method: SampleMethod, coordinate: f64, len: usize) -> Vec<(usize, f64)> {
+    let clamp = |idx: isize| idx.clamp(0, len as isize - 1) as usize;
+    // Cell centers are at half-integer screen coordinates.
+    let center_coordinate = coordinate - 0.5;
+    let base = center_coordinate.floor();
+    let t = center_coordinate - base;
+    let base = base as isize;
+    match method {
+        SampleMethod::Nearest => vec![(clamp(coordinate.floor() as isize), 1.)],
+        SampleMethod::Bilinear => vec![(clamp(base), 1. - t), (clamp(base + 1), t)],
+        SampleMethod::Bicubic => (-1..=2)
+            .map(|offset| (clamp(base + offset), cubic_weight(t - offset as f64)))
+            .collect(),
+    }
+}
+
+/// Keys cubic convolution kernel with a = -0.5.
+fn cubic_weight(distance: f64) -> f64 {
+    let a = -0.5;
+    let distance = distance.abs();
+    if distance <= 1. {
+        (a + 2.) * distance.powi(3) - (a + 3.) * distance.powi(2) + 1.
+    } else if distance < 2. {
+        a * distance.powi(3) - 5. * a * distance.powi(2) + 8. * a * distance - 4. * a
+    } else {
+        0.
+    }
+}
+
+impl Grid {
+    /// Sample a band of the grid at a world space location.
+    ///
+    /// Returns the nodata value if the location is outside of the grid
+    /// or any cell contributing to the sample is nodata.
+    ///
+    /// # Panics
+    /// Panics if `band` is out of range.
+    #[must_use]
+    pub fn sample(&self, x: f64, y: f64, band: usize, method: SampleMethod) -> f64 {
+        assert!(band < self.bands(), "band {band} is out of range");
+
+        let screen_point = self
+            .world_to_screen_transform
+            .transform_point(Point2D::new(x, y));
+        if !(0. ..=self.width as f64).contains(&screen_point.x)
+            || !(0. ..=self.height as f64).contains(&screen_point.y)
+        {
+            return self.nodata;
+        }
+
+        let column_weights = axis_weights(method, screen_point.x, self.width);
+        let row_weights = axis_weights(method, screen_point.y, self.height);
+
+        let mut value = 0.;
+        for (row, row_weight) in &row_weights {
+            for (column, column_weight) in &column_weights {
+                let weight = row_weight * column_weight;
+                if weight == 0. {
+                    continue;
+                }
+                let cell_value = self.data[[*row, *column, band]];
+                if equivalent(&cell_value, &self.nodata) {
+                    return self.nodata;
+                }
+                value += cell_value * weight;
+            }
+        }
+        value
+    }
+
+    /// Sample a band of the grid at the location of each point.
+    ///
+    /// See `sample` for how locations outside of the grid and nodata cells are handled.
+    ///
+    /// # Panics
+    /// Panics if `band` is out of range.
+    #[must_use]
+    pub fn sample_points(&self, points: &[Point], band: usize, method: SampleMethod) -> Vec<f64> {
+        points
+            .par_iter()
+            .map(|point| self.sample(point.x, point.y, band, method))
+            .collect()
+    }
+}
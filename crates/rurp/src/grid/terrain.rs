@@ -0,0 +1,183 @@
+use std::error::Error;
+
+use crate::cell_value::CellValue;
+use crate::grid::Grid;
+use ndarray::prelude::*;
+use rayon::prelude::*;
+
+/// The method used to estimate the gradient of a surface at a cell from its neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GradientMethod {
+    /// Horn's method, a weighted difference over all eight neighbors.
+    /// Smooths noise, suited to rough surfaces.
+    #[default]
+    Horn,
+    /// The Zevenbergen–Thorne method, a difference of the four side neighbors.
+    /// Follows the surface more closely, suited to smooth surfaces.
+    ZevenbergenThorne,
+}
+
+/// The 3 x 3 window of values around a cell, in rows from the top:
+/// top left, top, top right, left, center, right, bottom left, bottom, bottom right.
+type Window = [f64; 9];
+
+impl<T: CellValue> Grid<T> {
+    /// Calculate the slope of every band, in degrees from horizontal.
+    ///
+    /// Values and world units are assumed to share a unit, as in a DEM in a projected
+    /// coordinate system, and rows further from the bottom are assumed to be further north.
+    /// The result has the same geometry and a nodata value of NaN. Nodata cells stay nodata,
+    /// and neighbors that are nodata or outside of the grid take the value of the cell itself,
+    /// so edges and cells next to nodata still get a value.
+    #[must_use]
+    pub fn slope(&self, method: GradientMethod) -> Grid {
+        self.terrain_map(|window| {
+            let (east, north) = self.gradient(window, method);
+            Some(east.hypot(north).atan().to_degrees())
+        })
+    }
+
+    /// Calculate the aspect of every band, the direction the surface faces downhill
+    /// in degrees clockwise from north, from 0 up to 360.
+    ///
+    /// Flat cells have no aspect and are nodata. Cells are handled as in `slope`.
+    #[must_use]
+    pub fn aspect(&self, method: GradientMethod) -> Grid {
+        self.terrain_map(|window| {
+            let (east, north) = self.gradient(window, method);
+            (east != 0. || north != 0.).then(|| (-east).atan2(-north).to_degrees().rem_euclid(360.))
+        })
+    }
+
+    /// Calculate the hillshade of every band, the illumination of the surface
+    /// by a distant light, from 0 in shadow to 1 facing the light.
+    /// Cells are handled as in `slope`.
+    ///
+    /// # Arguments
+    /// `method` - The method used to estimate the gradient.
+    /// `azimuth` - The direction of the light, in degrees clockwise from north.
+    /// `altitude` - The angle of the light above the horizon, in degrees.
+    ///
+    /// # Errors
+    /// Returns an error if the altitude is not between 0 and 90 degrees or the azimuth is not finite.
+    pub fn hillshade(
+        &self,
+        method: GradientMethod,
+        azimuth: f64,
+        altitude: f64,
+    ) -> Result<Grid, Box<dyn Error>> {
+        if !(0. ..=90.).contains(&altitude) {
+            return Err("Altitude must be between 0 and 90 degrees".into());
+        }
+        if !azimuth.is_finite() {
+            return Err("Azimuth must be finite".into());
+        }
+
+        let (azimuth, altitude) = (azimuth.to_radians(), altitude.to_radians());
+        let light = [
+            azimuth.sin() * altitude.cos(),
+            azimuth.cos() * altitude.cos(),
+            altitude.sin(),
+        ];
+        Ok(self.terrain_map(|window| {
+            let (east, north) = self.gradient(window, method);
+            let normal_length = (east.powi(2) + north.powi(2) + 1.).sqrt();
+            let illumination = (-east * light[0] - north * light[1] + light[2]) / normal_length;
+            Some(illumination.max(0.))
+        }))
+    }
+
+    /// Calculate the curvature of every band with the Zevenbergen–Thorne method,
+    /// the negated sum of the second derivatives along rows and columns.
+    ///
+    /// Curvature is positive where the surface is convex, as on ridges,
+    /// and negative where it is concave, as in valleys, in units of one over world units.
+    /// Cells are handled as in `slope`.
+    #[must_use]
+    pub fn curvature(&self) -> Grid {
+        let (cell_width, cell_height) = (self.cell_width, self.cell_height);
+        self.terrain_map(|window| {
+            let [_, top, _, left, center, right, _, bottom, _] = *window;
+            let along_rows = ((left + right) / 2. - center) / cell_width.powi(2);
+            let along_columns = ((top + bottom) / 2. - center) / cell_height.powi(2);
+            Some(-2. * (along_rows + along_columns))
+        })
+    }
+
+    /// Estimate the gradient of the surface at the center of a window,
+    /// as the change in value per world unit towards the east and north.
+    fn gradient(&self, window: &Window, method: GradientMethod) -> (f64, f64) {
+        let [top_left, top, top_right, left, _, right, bottom_left, bottom, bottom_right] = *window;
+        match method {
+            GradientMethod::Horn => (
+                ((top_right + 2. * right + bottom_right) - (top_left + 2. * left + bottom_left))
+                    / (8. * self.cell_width),
+                ((top_left + 2. * top + top_right) - (bottom_left + 2. * bottom + bottom_right))
+                    / (8. * self.cell_height),
+            ),
+            GradientMethod::ZevenbergenThorne => (
+                (right - left) / (2. * self.cell_width),
+                (top - bottom) / (2. * self.cell_height),
+            ),
+        }
+    }
+
+    /// Get the 3 x 3 window of values around a cell, or None if the cell is nodata.
+    ///
+    /// Neighbors that are nodata or outside of the grid take the value of the center cell.
+    fn terrain_window(&self, row: usize, column: usize, band: usize) -> Option<Window> {
+        let center = self.data[[row, column, band]];
+        if center.equivalent(&self.nodata) {
+            return None;
+        }
+        let center = center.to_f64();
+
+        let mut window = [center; 9];
+        for (idx, (row_offset, column_offset)) in [1, 0, -1]
+            .into_iter()
+            .flat_map(|row_offset| [-1, 0, 1].map(|column_offset| (row_offset, column_offset)))
+            .enumerate()
+        {
+            let (Some(neighbor_row), Some(neighbor_column)) = (
+                row.checked_add_signed(row_offset),
+                column.checked_add_signed(column_offset),
+            ) else {
+                continue;
+            };
+            let Some(value) = self.data.get([neighbor_row, neighbor_column, band]) else {
+                continue;
+            };
+            if !value.equivalent(&self.nodata) {
+                window[idx] = value.to_f64();
+            }
+        }
+        Some(window)
+    }
+
+    /// Calculate a value from the window around every cell of every band,
+    /// into a new grid with the same geometry and a nodata value of NaN.
+    ///
+    /// Nodata cells and windows calculated as None are nodata in the result.
+    fn terrain_map<F>(&self, calculate: F) -> Grid
+    where
+        F: Fn(&Window) -> Option<f64> + Sync,
+    {
+        let (height, width, bands) = (self.height, self.width, self.bands());
+
+        let values: Vec<f64> = (0..height * width * bands)
+            .into_par_iter()
+            .map(|idx| {
+                let (row, column, band) = (idx / (width * bands), idx / bands % width, idx % bands);
+                self.terrain_window(row, column, band)
+                    .and_then(|window| calculate(&window))
+                    .unwrap_or(f64::NAN)
+            })
+            .collect();
+
+        self.with_data(
+            Array3::from_shape_vec((height, width, bands), values)
+                .expect("terrain values match the grid shape"),
+            f64::NAN,
+        )
+    }
+}
@@ -0,0 +1,408 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::bounds::Bounds;
+use crate::cell_value::CellValue;
+use crate::grid::{Grid, Registration, ScreenSpace, WorldSpace};
+use crate::resolution::{ExtentPolicy, Resolution};
+use euclid::{Point2D, Transform2D};
+use ndarray::prelude::*;
+
+/// A driver that processes a grid one tile at a time,
+/// for domains that are too large to hold in memory.
+///
+/// Only the geometry of the whole grid is kept, so a `TiledGrid` holds no cell data
+/// and has no `Grid` operations of its own. Each tile is created as a `Grid`
+/// covering its own cells plus a halo of neighboring cells, so neighborhood methods
+/// see the same surroundings they would on the whole grid.
+/// `process` hands each tile to a closure, where the whole `Grid` API is available,
+/// and writes the result to a `TileSink`; `assemble` collects the results into
+/// a single `Grid` for grids that fit in memory.
+#[derive(Debug)]
+pub struct TiledGrid<T: CellValue = f64> {
+    /// The whole grid, with no bands.
+    geometry: Grid<T>,
+    tile_height: usize,
+    tile_width: usize,
+    halo: usize,
+}
+
+impl<T: CellValue> TiledGrid<T> {
+    /// Get the bounds of the whole grid.
+    #[must_use]
+    pub fn bounds(&self) -> Bounds {
+        self.geometry.bounds()
+    }
+
+    /// Get the height of the whole grid.
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.geometry.height()
+    }
+
+    /// Get the width of the whole grid.
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.geometry.width()
+    }
+
+    /// Get the width of a cell in world units.
+    #[must_use]
+    pub fn cell_width(&self) -> f64 {
+        self.geometry.cell_width()
+    }
+
+    /// Get the height of a cell in world units.
+    #[must_use]
+    pub fn cell_height(&self) -> f64 {
+        self.geometry.cell_height()
+    }
+
+    /// Get the registration of the cells to the bounds.
+    #[must_use]
+    pub fn registration(&self) -> Registration {
+        self.geometry.registration()
+    }
+
+    /// Get the transformation from screen space to world space.
+    #[must_use]
+    pub fn screen_to_world_transform(&self) -> Transform2D<f64, ScreenSpace, WorldSpace> {
+        self.geometry.screen_to_world_transform()
+    }
+
+    /// Get the transformation from world space to screen space.
+    #[must_use]
+    pub fn world_to_screen_transform(&self) -> Transform2D<f64, WorldSpace, ScreenSpace> {
+        self.geometry.world_to_screen_transform()
+    }
+
+    /// Get the nodata value.
+    #[must_use]
+    pub fn nodata(&self) -> T {
+        self.geometry.nodata()
+    }
+
+    /// Get the maximum height of a tile, excluding the halo.
+    #[must_use]
+    pub fn tile_height(&self) -> usize {
+        self.tile_height
+    }
+
+    /// Get the maximum width of a tile, excluding the halo.
+    #[must_use]
+    pub fn tile_width(&self) -> usize {
+        self.tile_width
+    }
+
+    /// Get the number of cells each tile extends beyond its own cells.
+    #[must_use]
+    pub fn halo(&self) -> usize {
+        self.halo
+    }
+
+    /// Get the number of rows of tiles.
+    #[must_use]
+    pub fn tile_rows(&self) -> usize {
+        self.height().div_ceil(self.tile_height)
+    }
+
+    /// Get the number of columns of tiles.
+    #[must_use]
+    pub fn tile_columns(&self) -> usize {
+        self.width().div_ceil(self.tile_width)
+    }
+
+    /// Get the number of tiles.
+    #[must_use]
+    pub fn tile_count(&self) -> usize {
+        self.tile_rows() * self.tile_columns()
+    }
+}
+
+impl<T: CellValue> TiledGrid<T> {
+    /// Create a new TiledGrid instance with the geometry of `Grid::empty_from_resolution`.
+    ///
+    /// # Arguments
+    /// * `tile_size` - The maximum height and width of a tile in cells, excluding the halo.
+    /// * `halo` - The number of cells each tile extends beyond its own cells.
+    ///
+    /// # Errors
+    /// Returns an error if the tile size is zero or the grid cannot be created.
+    pub fn from_resolution(
+        bounds: &Bounds,
+        resolution: &Resolution,
+        policy: ExtentPolicy,
+        registration: Registration,
+        nodata: T,
+        tile_size: (usize, usize),
+        halo: usize,
+    ) -> Result<Self, Box<dyn Error>> {
+        let geometry =
+            Grid::from_resolution_with_bands(bounds, resolution, policy, registration, 0, nodata)?;
+        TiledGrid::from_geometry(geometry, tile_size, halo)
+    }
+
+    fn from_geometry(
+        geometry: Grid<T>,
+        (tile_height, tile_width): (usize, usize),
+        halo: usize,
+    ) -> Result<Self, Box<dyn Error>> {
+        if tile_height == 0 || tile_width == 0 {
+            return Err("tile size must be positive".into());
+        }
+        Ok(TiledGrid {
+            geometry,
+            tile_height,
+            tile_width,
+            halo,
+        })
+    }
+}
+
+impl<T: CellValue> TiledGrid<T> {
+    /// Create the tile at `index`, with one band filled with the nodata value.
+    ///
+    /// Tiles are ordered by row of tiles from the bottom, then by column from the left.
+    /// The tile grid is registered as `Registration::PixelIsArea` with the outer edges
+    /// of its cells as bounds, and its cell coordinates match those of the whole grid.
+    ///
+    /// # Errors
+    /// Returns an error if `index` is out of range or the tile grid cannot be created.
+    pub fn tile(&self, index: usize) -> Result<Tile<T>, Box<dyn Error>> {
+        if index >= self.tile_count() {
+            return Err("tile index is out of range".into());
+        }
+        let (tile_row, tile_column) = (index / self.tile_columns(), index % self.tile_columns());
+
+        let core_rows =
+            tile_row * self.tile_height..((tile_row + 1) * self.tile_height).min(self.height());
+        let core_columns =
+            tile_column * self.tile_width..((tile_column + 1) * self.tile_width).min(self.width());
+        let rows = core_rows.start.saturating_sub(self.halo)
+            ..(core_rows.end + self.halo).min(self.height());
+        let columns = core_columns.start.saturating_sub(self.halo)
+            ..(core_columns.end + self.halo).min(self.width());
+
+        let screen_to_world = self.screen_to_world_transform();
+        let lower =
+            screen_to_world.transform_point(Point2D::new(columns.start as f64, rows.start as f64));
+        let upper =
+            screen_to_world.transform_point(Point2D::new(columns.end as f64, rows.end as f64));
+        let bounds = Bounds::new(lower.x, lower.y, upper.x, upper.y)?;
+
+        let grid = Grid::from_geometry(
+            &bounds,
+            (rows.len(), columns.len()),
+            (self.cell_height(), self.cell_width()),
+            Registration::PixelIsArea,
+            1,
+            self.nodata(),
+        )?;
+
+        Ok(Tile {
+            grid,
+            rows,
+            columns,
+            core_rows,
+            core_columns,
+        })
+    }
+
+    /// Get an iterator that creates each tile in turn.
+    pub fn tiles(&self) -> impl Iterator<Item = Result<Tile<T>, Box<dyn Error>>> + '_ {
+        (0..self.tile_count()).map(|index| self.tile(index))
+    }
+
+    /// Process the grid one tile at a time, writing each processed tile to `sink`.
+    ///
+    /// Only one tile is held in memory at a time.
+    ///
+    /// # Arguments
+    /// * `process` - Fills a tile grid, e.g. by interpolating onto it.
+    /// * `sink` - Receives the cells each tile owns, excluding the halo.
+    ///
+    /// # Errors
+    /// Returns an error if a tile cannot be created, processed or written.
+    pub fn process<F, S>(&self, mut process: F, sink: &mut S) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(&mut Grid<T>) -> Result<(), Box<dyn Error>>,
+        S: TileSink<T> + ?Sized,
+    {
+        self.tiles().try_for_each(|tile| {
+            let mut tile = tile?;
+            process(tile.grid_mut())?;
+            sink.write_tile(&tile)
+        })
+    }
+
+    /// Process the grid one tile at a time and assemble the tiles into a single grid.
+    ///
+    /// This is for grids that fit in memory; the result has the geometry of the whole grid.
+    ///
+    /// # Errors
+    /// Returns an error if a tile cannot be created or processed.
+    pub fn assemble<F>(&self, process: F) -> Result<Grid<T>, Box<dyn Error>>
+    where
+        F: FnMut(&mut Grid<T>) -> Result<(), Box<dyn Error>>,
+    {
+        let mut grid = self.geometry.cast(self.nodata());
+        self.process(process, &mut grid)?;
+        Ok(grid)
+    }
+}
+
+/// A tile of a `TiledGrid`.
+#[derive(Debug)]
+pub struct Tile<T: CellValue = f64> {
+    grid: Grid<T>,
+    rows: Range<usize>,
+    columns: Range<usize>,
+    core_rows: Range<usize>,
+    core_columns: Range<usize>,
+}
+
+impl<T: CellValue> Tile<T> {
+    /// Get the grid of the tile, including the halo.
+    #[must_use]
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    /// Get the mutable grid of the tile, including the halo.
+    pub fn grid_mut(&mut self) -> &mut Grid<T> {
+        &mut self.grid
+    }
+
+    /// Get the rows of the whole grid covered by the tile, including the halo.
+    #[must_use]
+    pub fn rows(&self) -> Range<usize> {
+        self.rows.clone()
+    }
+
+    /// Get the columns of the whole grid covered by the tile, including the halo.
+    #[must_use]
+    pub fn columns(&self) -> Range<usize> {
+        self.columns.clone()
+    }
+
+    /// Get the rows of the whole grid owned by the tile.
+    #[must_use]
+    pub fn core_rows(&self) -> Range<usize> {
+        self.core_rows.clone()
+    }
+
+    /// Get the columns of the whole grid owned by the tile.
+    #[must_use]
+    pub fn core_columns(&self) -> Range<usize> {
+        self.core_columns.clone()
+    }
+
+    /// Get a view of the data of the cells owned by the tile, excluding the halo.
+    #[must_use]
+    pub fn core(&self) -> ArrayView3<'_, T> {
+        let row_offset = self.core_rows.start - self.rows.start;
+        let column_offset = self.core_columns.start - self.columns.start;
+        self.grid.data.slice(s![
+            row_offset..row_offset + self.core_rows.len(),
+            column_offset..column_offset + self.core_columns.len(),
+            ..
+        ])
+    }
+}
+
+/// A destination for the tiles of a `TiledGrid`.
+pub trait TileSink<T: CellValue> {
+    /// Write the cells owned by a processed tile.
+    ///
+    /// # Errors
+    /// Returns an error if the tile cannot be written.
+    fn write_tile(&mut self, tile: &Tile<T>) -> Result<(), Box<dyn Error>>;
+}
+
+/// Tiles are written into the matching cells of a grid with the geometry of the whole grid.
+///
+/// The grid takes the band count of the tiles, and is reset when the band count changes.
+impl<T: CellValue> TileSink<T> for Grid<T> {
+    fn write_tile(&mut self, tile: &Tile<T>) -> Result<(), Box<dyn Error>> {
+        if tile.core_rows.end > self.height || tile.core_columns.end > self.width {
+            return Err("tile is outside of the grid".into());
+        }
+        let core = tile.core();
+        let bands = core.len_of(Axis(2));
+        if bands != self.bands() {
+            self.reset_bands(bands);
+        }
+        self.data
+            .slice_mut(s![tile.core_rows(), tile.core_columns(), ..])
+            .assign(&core);
+        Ok(())
+    }
+}
+
+/// Tiles are written into a raw binary file.
+///
+/// The file holds every cell of the whole grid in little-endian byte order,
+/// row by row from the bottom row, with the bands of each cell stored together.
+#[derive(Debug)]
+pub struct RawFileSink<T: CellValue = f64> {
+    file: BufWriter<File>,
+    width: usize,
+    bands: usize,
+    value_type: PhantomData<T>,
+}
+
+impl<T: CellValue> RawFileSink<T> {
+    /// Create a raw binary file for the tiles of `tiled_grid` with `bands` bands.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be created.
+    pub fn create(
+        path: impl AsRef<Path>,
+        tiled_grid: &TiledGrid<T>,
+        bands: usize,
+    ) -> Result<Self, Box<dyn Error>> {
+        let file = File::create(path)?;
+        let cells = tiled_grid.height() * tiled_grid.width() * bands;
+        file.set_len((cells * std::mem::size_of::<T>()) as u64)?;
+        Ok(RawFileSink {
+            file: BufWriter::new(file),
+            width: tiled_grid.width(),
+            bands,
+            value_type: PhantomData,
+        })
+    }
+
+    /// Flush any buffered tiles to the file.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    pub fn finish(mut self) -> Result<(), Box<dyn Error>> {
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+impl<T: CellValue> TileSink<T> for RawFileSink<T> {
+    fn write_tile(&mut self, tile: &Tile<T>) -> Result<(), Box<dyn Error>> {
+        let core = tile.core();
+        if core.len_of(Axis(2)) != self.bands {
+            return Err("tile band count does not match the file".into());
+        }
+        let value_size = std::mem::size_of::<T>();
+        let mut bytes = Vec::with_capacity(tile.core_columns.len() * self.bands * value_size);
+        for (row, row_data) in tile.core_rows().zip(core.outer_iter()) {
+            bytes.clear();
+            row_data
+                .iter()
+                .for_each(|value| value.extend_le_bytes(&mut bytes));
+            let offset = (row * self.width + tile.core_columns.start) * self.bands * value_size;
+            self.file.seek(SeekFrom::Start(offset as u64))?;
+            self.file.write_all(&bytes)?;
+        }
+        Ok(())
+    }
+}
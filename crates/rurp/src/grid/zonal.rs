@@ -0,0 +1,166 @@
+use std::error::Error;
+
+use crate::cell_value::CellValue;
+use crate::grid::Grid;
+use geo::Geometry;
+use ndarray::prelude::*;
+use rayon::prelude::*;
+
+/// Options for computing zonal statistics.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ZonalOptions {
+    /// The band to compute statistics of.
+    pub band: usize,
+    /// Weight every cell a polygonal zone overlaps by the fraction of the cell it covers,
+    /// instead of counting every cell it touches fully.
+    /// Useful for zones that are small relative to the cells.
+    pub coverage_fraction: bool,
+    /// The percentiles to compute, between 0 and 100.
+    pub percentiles: Vec<f64>,
+}
+
+/// Statistics of the cells of a grid within one zone.
+///
+/// With coverage fractions, every statistic but `count`, `min` and `max` is weighted by
+/// the covered fraction of each cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneStatistics {
+    /// The number of cells with data in the zone.
+    pub count: usize,
+    /// The summed weight of the cells with data, equal to `count` without coverage fractions.
+    pub weight: f64,
+    pub sum: f64,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    /// The population standard deviation.
+    pub stddev: f64,
+    /// The value at each of the requested percentiles, in the requested order.
+    pub percentiles: Vec<f64>,
+}
+
+impl<T: CellValue> Grid<T> {
+    /// Compute statistics of the cells of a band within each zone, in the order of the zones.
+    /// The zones are assumed to be in world space.
+    ///
+    /// Zones are independent, so a cell in several overlapping zones counts towards each of them.
+    /// Nodata cells are ignored, and zones without cells with data get None.
+    /// Without coverage fractions, zones include every cell they touch,
+    /// the cells `rasterize_polygons` burns for them.
+    ///
+    /// Percentiles interpolate linearly between the sorted values, with each value positioned
+    /// at the midpoint of its weight, `(cumulative weight - weight / 2) / total weight`.
+    /// With equal weights, value `i` of `n` is positioned at `(i + 0.5) / n`.
+    /// Percentiles before the first or after the last position take the minimum or maximum.
+    ///
+    /// # Arguments
+    /// `zones` - The zones, typically polygons or multi polygons.
+    /// `options` - The band, weighting and percentiles to use.
+    ///
+    /// # Errors
+    /// Returns an error if the band is out of range, a percentile is not between 0 and 100,
+    /// coverage fractions are requested for a zone that is not polygonal,
+    /// or the rasterization fails.
+    pub fn zonal_statistics(
+        &self,
+        zones: &[Geometry<f64>],
+        options: &ZonalOptions,
+    ) -> Result<Vec<Option<ZoneStatistics>>, Box<dyn Error>> {
+        if options.band >= self.bands() {
+            return Err("Band is out of range".into());
+        }
+        if !options
+            .percentiles
+            .iter()
+            .all(|percentile| (0. ..=100.).contains(percentile))
+        {
+            return Err("Percentiles must be between 0 and 100".into());
+        }
+
+        let zone_values = zones
+            .iter()
+            .map(|zone| {
+                let Some(((rows, columns), weights)) =
+                    self.cell_weights(zone, true, options.coverage_fraction)?
+                else {
+                    return Ok(vec![]);
+                };
+                let values = self.data.slice(s![rows, columns, options.band]);
+                Ok(values
+                    .iter()
+                    .zip(&weights)
+                    .filter(|(value, &weight)| weight > 0. && !value.equivalent(&self.nodata))
+                    .map(|(value, &weight)| (value.to_f64(), weight))
+                    .collect())
+            })
+            .collect::<Result<Vec<Vec<(f64, f64)>>, Box<dyn Error>>>()?;
+
+        Ok(zone_values
+            .into_par_iter()
+            .map(|weighted_values| zone_statistics(weighted_values, &options.percentiles))
+            .collect())
+    }
+}
+
+/// Compute the statistics of weighted values, or None if there are none.
+fn zone_statistics(
+    mut weighted_values: Vec<(f64, f64)>,
+    percentiles: &[f64],
+) -> Option<ZoneStatistics> {
+    if weighted_values.is_empty() {
+        return None;
+    }
+    weighted_values.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let weight: f64 = weighted_values.iter().map(|(_, weight)| weight).sum();
+    let sum: f64 = weighted_values
+        .iter()
+        .map(|(value, weight)| value * weight)
+        .sum();
+    let mean = sum / weight;
+    let variance = weighted_values
+        .iter()
+        .map(|(value, weight)| weight * (value - mean).powi(2))
+        .sum::<f64>()
+        / weight;
+
+    Some(ZoneStatistics {
+        count: weighted_values.len(),
+        weight,
+        sum,
+        mean,
+        min: weighted_values[0].0,
+        max: weighted_values[weighted_values.len() - 1].0,
+        stddev: variance.sqrt(),
+        percentiles: percentiles
+            .iter()
+            .map(|&percentile| weighted_percentile(&weighted_values, percentile))
+            .collect(),
+    })
+}
+
+/// Get a percentile of non-empty weighted values sorted by value,
+/// using the weighted midpoint definition described on `zonal_statistics`.
+fn weighted_percentile(sorted_values: &[(f64, f64)], percentile: f64) -> f64 {
+    let total: f64 = sorted_values.iter().map(|(_, weight)| weight).sum();
+    let target = percentile / 100.;
+
+    let mut cumulative = 0.;
+    let mut previous: Option<(f64, f64)> = None;
+    for &(value, weight) in sorted_values {
+        let position = (cumulative + weight / 2.) / total;
+        cumulative += weight;
+        if target <= position {
+            return match previous {
+                Some((previous_value, previous_position)) if position > previous_position => {
+                    previous_value
+                        + (value - previous_value) * (target - previous_position)
+                            / (position - previous_position)
+                }
+                _ => value,
+            };
+        }
+        previous = Some((value, position));
+    }
+    sorted_values[sorted_values.len() - 1].0
+}
@@ -1,12 +1,152 @@
+use std::error::Error;
+
+use crate::point::Point;
+use kiddo::{KdTree, NearestNeighbour as NearestNeighbor, SquaredEuclidean};
+
+pub mod binning;
 pub mod inverse_distance_weighting;
 pub mod inverse_distance_weighting_global;
+pub mod moving_window;
 pub mod natural_neighbor;
 pub mod nearest_neighbor;
+pub mod space_time;
 
 pub enum InterpolationMethod {
+    Binning,
     InverseDistanceWeighting,
     InverseDistanceWeightingGlobal,
+    MovingWindow,
     NaturalNeighbor,
     NearestNeighbor,
+    SpaceTimeInverseDistanceWeighting,
     // Kriging,
 }
+
+/// An interpolation method with its parameters, able to estimate values at arbitrary locations.
+pub trait Interpolator: Sync {
+    /// Estimate the value at each location from the points.
+    ///
+    /// Locations the method cannot estimate a value for are `None`.
+    ///
+    /// # Errors
+    /// Returns an error if the method cannot be applied to the points.
+    fn predict(
+        &self,
+        points: &[Point],
+        locations: &[(f64, f64)],
+    ) -> Result<Vec<Option<f64>>, Box<dyn Error + Send + Sync>>;
+}
+
+/// A kd-tree of positions for neighbor queries, with points identified by their index.
+///
+/// The kd-tree cannot split more points than fit in one of its buckets when they share a
+/// coordinate, as duplicate points, points on a line along an axis or points observed at one
+/// time do. Tied coordinates are spread apart by a few units in the last place inside the tree,
+/// and queries measure distances from the original positions.
+pub(crate) struct PointTree<const K: usize> {
+    tree: KdTree<f64, K>,
+    positions: Vec<[f64; K]>,
+    /// The largest distance a position was moved along one axis inside the tree.
+    slack: f64,
+}
+
+impl PointTree<2> {
+    /// Build a tree of the points in space.
+    pub(crate) fn from_points(points: &[Point]) -> Self {
+        Self::new(points.iter().map(|point| [point.x, point.y]).collect())
+    }
+}
+
+impl<const K: usize> PointTree<K> {
+    pub(crate) fn new(positions: Vec<[f64; K]>) -> Self {
+        let mut spread = positions.clone();
+        let mut slack: f64 = 0.;
+        let mut order: Vec<usize> = (0..positions.len()).collect();
+        for axis in 0..K {
+            order.sort_by(|&a, &b| positions[a][axis].total_cmp(&positions[b][axis]));
+            for idx in 1..order.len() {
+                let previous = spread[order[idx - 1]][axis];
+                let position = &mut spread[order[idx]][axis];
+                if *position <= previous {
+                    *position = previous.next_up();
+                    slack = slack.max(*position - positions[order[idx]][axis]);
+                }
+            }
+        }
+
+        let tree = spread
+            .iter()
+            .enumerate()
+            .map(|(idx, position)| (*position, idx as u64))
+            .collect();
+        Self {
+            tree,
+            positions,
+            slack,
+        }
+    }
+
+    /// Get the points closer than the squared radius, nearest first.
+    pub(crate) fn within(
+        &self,
+        query: &[f64; K],
+        radius_squared: f64,
+    ) -> Vec<NearestNeighbor<f64, u64>> {
+        let search_radius = radius_squared.sqrt() + self.slack * (K as f64).sqrt();
+        let mut neighbors: Vec<_> = self
+            .measured(
+                self.tree
+                    .within::<SquaredEuclidean>(query, search_radius.powi(2)),
+                query,
+            )
+            .filter(|neighbor| neighbor.distance < radius_squared)
+            .collect();
+        neighbors.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        neighbors
+    }
+
+    /// Get the `count` nearest points, nearest first.
+    ///
+    /// Points whose distances differ by less than the spread of tied coordinates may be chosen
+    /// in any order.
+    pub(crate) fn nearest_n(
+        &self,
+        query: &[f64; K],
+        count: usize,
+    ) -> Vec<NearestNeighbor<f64, u64>> {
+        let mut neighbors: Vec<_> = self
+            .measured(self.tree.nearest_n::<SquaredEuclidean>(query, count), query)
+            .collect();
+        neighbors.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        neighbors
+    }
+
+    /// Get the nearest point. See `nearest_n` for how near ties are chosen.
+    pub(crate) fn nearest_one(&self, query: &[f64; K]) -> NearestNeighbor<f64, u64> {
+        let nearest = self.tree.nearest_one::<SquaredEuclidean>(query);
+        NearestNeighbor {
+            distance: self.distance_squared(nearest.item, query),
+            item: nearest.item,
+        }
+    }
+
+    /// Replace the distances of neighbors in the tree with their distances from the original positions.
+    fn measured<'a>(
+        &'a self,
+        neighbors: Vec<NearestNeighbor<f64, u64>>,
+        query: &'a [f64; K],
+    ) -> impl Iterator<Item = NearestNeighbor<f64, u64>> + 'a {
+        neighbors.into_iter().map(|neighbor| NearestNeighbor {
+            distance: self.distance_squared(neighbor.item, query),
+            item: neighbor.item,
+        })
+    }
+
+    fn distance_squared(&self, item: u64, query: &[f64; K]) -> f64 {
+        self.positions[item as usize]
+            .iter()
+            .zip(query)
+            .map(|(position, query)| (position - query).powi(2))
+            .sum()
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use crate::grid::Grid;
+use crate::point::Point;
+use euclid::Point2D;
+use rayon::prelude::*;
+
+/// An aggregation of the values of the points that fall into each grid cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    Mean,
+    Min,
+    Max,
+    Count,
+    Sum,
+    /// The value of the point that comes last in the input.
+    Last,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bin {
+    count: usize,
+    sum: f64,
+    min: f64,
+    max: f64,
+    last: Option<(usize, f64)>,
+}
+
+impl Default for Bin {
+    fn default() -> Self {
+        Bin {
+            count: 0,
+            sum: 0.,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            last: None,
+        }
+    }
+}
+
+impl Bin {
+    fn add(&mut self, idx: usize, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        if self.last.is_none_or(|(last_idx, _)| idx > last_idx) {
+            self.last = Some((idx, value));
+        }
+    }
+
+    fn merge(&mut self, other: &Bin) {
+        self.count += other.count;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        if let Some((idx, value)) = other.last {
+            if self.last.is_none_or(|(last_idx, _)| idx > last_idx) {
+                self.last = Some((idx, value));
+            }
+        }
+    }
+
+    fn aggregate(&self, aggregation: Aggregation) -> f64 {
+        match aggregation {
+            Aggregation::Mean => self.sum / self.count as f64,
+            Aggregation::Min => self.min,
+            Aggregation::Max => self.max,
+            Aggregation::Count => self.count as f64,
+            Aggregation::Sum => self.sum,
+            Aggregation::Last => self.last.map_or(f64::NAN, |(_, value)| value),
+        }
+    }
+}
+
+/// Get the index of the cell containing a world space location, if it is inside the grid.
+///
+/// Locations on the right or top edge of the grid belong to the last column or row.
+fn cell_index(grid: &Grid, x: f64, y: f64) -> Option<usize> {
+    let screen_point = grid
+        .world_to_screen_transform()
+        .transform_point(Point2D::new(x, y));
+    let (width, height) = (grid.width() as f64, grid.height() as f64);
+    if !(0. ..=width).contains(&screen_point.x) || !(0. ..=height).contains(&screen_point.y) {
+        return None;
+    }
+    let column = (screen_point.x.floor() as usize).min(grid.width() - 1);
+    let row = (screen_point.y.floor() as usize).min(grid.height() - 1);
+    Some(row * grid.width() + column)
+}
+
+/// Aggregates the points that fall into each grid cell without any neighbor search.
+///
+/// The grid is reset to one band per aggregation, in the order given.
+/// Points outside of the grid are ignored and cells without points are left as nodata,
+/// except for `Aggregation::Count` which is always written.
+///
+/// # Arguments
+/// `grid` - The grid to write to.
+/// `points` - The points to aggregate.
+/// `aggregations` - The aggregations to compute, one band each.
+pub fn interpolate(grid: &mut Grid, points: &[Point], aggregations: &[Aggregation]) {
+    // Bins are kept only for cells with points, so memory follows the points rather than the grid.
+    let bins = points
+        .par_iter()
+        .enumerate()
+        .fold(
+            HashMap::new,
+            |mut bins: HashMap<usize, Bin>, (idx, point)| {
+                if let Some(cell) = cell_index(grid, point.x, point.y) {
+                    bins.entry(cell).or_default().add(idx, point.values[0]);
+                }
+                bins
+            },
+        )
+        .reduce(HashMap::new, |bins, other| {
+            if bins.len() < other.len() {
+                return merge_bins(other, &bins);
+            }
+            merge_bins(bins, &other)
+        });
+
+    grid.reset_bands(aggregations.len());
+
+    let empty = Bin::default();
+    grid.iter_world_bands_mut()
+        .enumerate()
+        .par_bridge()
+        .for_each(|(cell, (_, _, mut grid_values))| {
+            let bin = bins.get(&cell).unwrap_or(&empty);
+            aggregations.iter().zip(grid_values.iter_mut()).for_each(
+                |(aggregation, grid_value)| {
+                    if bin.count > 0 || *aggregation == Aggregation::Count {
+                        *grid_value = bin.aggregate(*aggregation);
+                    }
+                },
+            );
+        });
+}
+
+/// Merge the bins of other points into bins, by cell.
+fn merge_bins(mut bins: HashMap<usize, Bin>, other: &HashMap<usize, Bin>) -> HashMap<usize, Bin> {
+    other.iter().for_each(|(cell, other)| {
+        bins.entry(*cell).or_default().merge(other);
+    });
+    bins
+}
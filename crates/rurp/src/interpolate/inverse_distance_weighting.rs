@@ -1,19 +1,59 @@
+use std::error::Error;
+
 use crate::grid::Grid;
+use crate::interpolate::{Interpolator, PointTree};
 use crate::point::Point;
-use kiddo::{KdTree, NearestNeighbour as NearestNeighbor, SquaredEuclidean};
+use kiddo::NearestNeighbour as NearestNeighbor;
 use rayon::prelude::*;
 
-fn calculate_interpolated_value(
+/// The Inverse Distance Weighting method with its parameters.
+///
+/// See `interpolate` for a description of the parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InverseDistanceWeighting {
+    pub power: f64,
+    pub radius: f64,
+    pub min_neighbors: usize,
+}
+
+impl Interpolator for InverseDistanceWeighting {
+    fn predict(
+        &self,
+        points: &[Point],
+        locations: &[(f64, f64)],
+    ) -> Result<Vec<Option<f64>>, Box<dyn Error + Send + Sync>> {
+        let point_tree = PointTree::from_points(points);
+        let radius_squared = self.radius.powi(2);
+        Ok(locations
+            .par_iter()
+            .map(|(x, y)| {
+                let neighbors = point_tree.within(&[*x, *y], radius_squared);
+                (!neighbors.is_empty() && neighbors.len() >= self.min_neighbors)
+                    .then(|| calculate_interpolated_value(&neighbors, points, self.power))
+            })
+            .collect())
+    }
+}
+
+/// Calculate the weighted mean of the neighbors' values.
+///
+/// Neighbors at the location itself take all of the weight, shared equally between them.
+pub(crate) fn calculate_interpolated_value(
     neighbors: &[NearestNeighbor<f64, u64>],
     points: &[Point],
     power: f64,
 ) -> f64 {
+    let has_exact_match = neighbors.iter().any(|neighbor| neighbor.distance == 0.0);
     let mut weights_sum = 0.;
     let weights: Vec<_> = neighbors
         .iter()
         .map(|neighbor| {
-            let weight = if neighbor.distance == 0.0 {
-                f64::INFINITY
+            let weight = if has_exact_match {
+                if neighbor.distance == 0.0 {
+                    1.
+                } else {
+                    0.
+                }
             } else {
                 // distance is the squared distance
                 // (distance ^ (1/2)) ^ -power == distance ^ -(power / 2)
@@ -41,6 +81,8 @@ fn calculate_interpolated_value(
 /// `power` - The power used in the interpolation. Values are weighted by 1 / distance ^ power.
 /// `radius` - The radius to search for points to interpolate from for each grid point. Points outside of this radius are ignored.
 /// `min_neighbors` - The minimum number of points in radius required to interpolate a value.
+///
+/// Cells at the location of one or more points take the mean of their values.
 pub fn interpolate(
     grid: &mut Grid,
     points: &[Point],
@@ -48,18 +90,14 @@ pub fn interpolate(
     radius: f64,
     min_neighbors: usize,
 ) {
-    let point_tree: KdTree<f64, 2> = points
-        .iter()
-        .enumerate()
-        .map(|(idx, point)| ([point.x, point.y], idx as u64))
-        .collect();
+    let point_tree = PointTree::from_points(points);
 
     let radius_squared = radius.powi(2);
 
     grid.iter_world_mut()
         .par_bridge()
         .for_each(|(x, y, grid_value)| {
-            let neighbors = point_tree.within::<SquaredEuclidean>(&[x, y], radius_squared);
+            let neighbors = point_tree.within(&[x, y], radius_squared);
             if !neighbors.is_empty() && neighbors.len() >= min_neighbors {
                 *grid_value = calculate_interpolated_value(&neighbors, points, power);
             }
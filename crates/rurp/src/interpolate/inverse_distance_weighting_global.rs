@@ -1,23 +1,62 @@
+use std::error::Error;
+
 use crate::grid::Grid;
+use crate::interpolate::Interpolator;
 use crate::point::Point;
 use geo::EuclideanDistance;
 use rayon::prelude::*;
 
-fn calculate_weight(point_a: &geo::Point, point_b: &geo::Point, power: f64) -> f64 {
-    let distance = point_a.euclidean_distance(point_b);
-    if distance == 0.0 {
-        return f64::INFINITY;
+/// The global Inverse Distance Weighting method with its parameters.
+///
+/// See `interpolate` for a description of the parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InverseDistanceWeightingGlobal {
+    pub power: f64,
+}
+
+impl Interpolator for InverseDistanceWeightingGlobal {
+    fn predict(
+        &self,
+        points: &[Point],
+        locations: &[(f64, f64)],
+    ) -> Result<Vec<Option<f64>>, Box<dyn Error + Send + Sync>> {
+        Ok(locations
+            .par_iter()
+            .map(|(x, y)| {
+                (!points.is_empty())
+                    .then(|| calculate_interpolated_value(*x, *y, points, self.power))
+            })
+            .collect())
     }
+}
+
+fn calculate_weight(distance: f64, power: f64) -> f64 {
     distance.powf(-power)
 }
 
+/// Calculate the weighted mean of the values of all points.
+///
+/// Points at the location itself take all of the weight, shared equally between them.
 fn calculate_interpolated_value(x: f64, y: f64, points: &[Point], power: f64) -> f64 {
     let grid_point = geo::Point::new(x, y);
+    let distances: Vec<f64> = points
+        .iter()
+        .map(|point| geo::Point::from(point).euclidean_distance(&grid_point))
+        .collect();
+    let has_exact_match = distances.contains(&0.0);
     let mut weights_sum = 0.;
-    let weights: Vec<_> = points
+    let weights: Vec<_> = distances
         .iter()
-        .map(|point| {
-            let weight = calculate_weight(&point.into(), &grid_point, power);
+        .map(|distance| {
+            let weight = if has_exact_match {
+                if *distance == 0.0 {
+                    1.
+                } else {
+                    0.
+                }
+            } else {
+                calculate_weight(*distance, power)
+            };
             weights_sum += weight;
             weight
         })
@@ -30,6 +69,8 @@ fn calculate_interpolated_value(x: f64, y: f64, points: &[Point], power: f64) ->
 }
 
 /// Interpolates to the grid using the Inverse Distance Weighting method.
+///
+/// Cells at the location of one or more points take the mean of their values.
 pub fn interpolate(grid: &mut Grid, points: &[Point], power: f64) {
     grid.iter_world_mut()
         .par_bridge()
@@ -0,0 +1,154 @@
+use std::error::Error;
+
+use crate::grid::Grid;
+use crate::interpolate::{Interpolator, PointTree};
+use crate::point::Point;
+use kiddo::NearestNeighbour as NearestNeighbor;
+use rayon::prelude::*;
+
+/// A statistic computed over the values of the points in a moving window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Statistic {
+    Count,
+    Mean,
+    Median,
+    Min,
+    Max,
+    Range,
+    StandardDeviation,
+}
+
+/// The points gathered around each grid cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Neighborhood {
+    /// All points within a radius of the cell.
+    Radius(f64),
+    /// The k nearest points to the cell.
+    Nearest(usize),
+}
+
+/// The moving window method for a single statistic with its parameters.
+///
+/// See `interpolate` for a description of the parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovingWindow {
+    pub statistic: Statistic,
+    pub neighborhood: Neighborhood,
+    pub min_neighbors: usize,
+}
+
+impl Interpolator for MovingWindow {
+    fn predict(
+        &self,
+        points: &[Point],
+        locations: &[(f64, f64)],
+    ) -> Result<Vec<Option<f64>>, Box<dyn Error + Send + Sync>> {
+        let point_tree = PointTree::from_points(points);
+        Ok(locations
+            .par_iter()
+            .map(|(x, y)| {
+                let values = window_values(&point_tree, points, self.neighborhood, *x, *y);
+                if !values.is_empty() && values.len() >= self.min_neighbors {
+                    Some(calculate_statistic(self.statistic, &values))
+                } else if self.statistic == Statistic::Count {
+                    Some(values.len() as f64)
+                } else {
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+/// Get the values of the points in the neighborhood of a location.
+fn window_values(
+    point_tree: &PointTree<2>,
+    points: &[Point],
+    neighborhood: Neighborhood,
+    x: f64,
+    y: f64,
+) -> Vec<f64> {
+    let neighbors: Vec<NearestNeighbor<f64, u64>> = match neighborhood {
+        Neighborhood::Radius(radius) => point_tree.within(&[x, y], radius.powi(2)),
+        Neighborhood::Nearest(count) => point_tree.nearest_n(&[x, y], count),
+    };
+    neighbors
+        .iter()
+        .map(|neighbor| points[neighbor.item as usize].values[0])
+        .collect()
+}
+
+fn calculate_statistic(statistic: Statistic, values: &[f64]) -> f64 {
+    let count = values.len() as f64;
+    let mean = || values.iter().sum::<f64>() / count;
+    let min = || values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = || values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    match statistic {
+        Statistic::Count => count,
+        Statistic::Mean => mean(),
+        Statistic::Median => {
+            let mut sorted = values.to_vec();
+            sorted.sort_by(f64::total_cmp);
+            let mid = sorted.len() / 2;
+            if sorted.len().is_multiple_of(2) {
+                (sorted[mid - 1] + sorted[mid]) / 2.
+            } else {
+                sorted[mid]
+            }
+        }
+        Statistic::Min => min(),
+        Statistic::Max => max(),
+        Statistic::Range => max() - min(),
+        Statistic::StandardDeviation => {
+            let mean = mean();
+            (values
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f64>()
+                / count)
+                .sqrt()
+        }
+    }
+}
+
+/// Computes statistics of the points in a moving window around each grid cell.
+///
+/// The grid is reset to one band per statistic, in the order given.
+/// Cells with fewer than `min_neighbors` points, or no points at all, are left as nodata,
+/// except for `Statistic::Count` which is always written.
+///
+/// # Arguments
+/// `grid` - The grid to write to.
+/// `points` - The points to compute statistics from.
+/// `statistics` - The statistics to compute, one band each.
+/// `neighborhood` - The points gathered around each grid cell.
+/// `min_neighbors` - The minimum number of points in the window required to compute a statistic.
+pub fn interpolate(
+    grid: &mut Grid,
+    points: &[Point],
+    statistics: &[Statistic],
+    neighborhood: Neighborhood,
+    min_neighbors: usize,
+) {
+    let point_tree = PointTree::from_points(points);
+
+    grid.reset_bands(statistics.len());
+
+    grid.iter_world_bands_mut()
+        .par_bridge()
+        .for_each(|(x, y, mut grid_values)| {
+            let values = window_values(&point_tree, points, neighborhood, x, y);
+            let enough_neighbors = !values.is_empty() && values.len() >= min_neighbors;
+
+            statistics
+                .iter()
+                .zip(grid_values.iter_mut())
+                .for_each(|(statistic, grid_value)| {
+                    if enough_neighbors {
+                        *grid_value = calculate_statistic(*statistic, &values);
+                    } else if *statistic == Statistic::Count {
+                        *grid_value = values.len() as f64;
+                    }
+                });
+        });
+}
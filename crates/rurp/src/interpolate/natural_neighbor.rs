@@ -1,18 +1,33 @@
 use std::error::Error;
 
 use crate::grid::Grid;
+use crate::interpolate::Interpolator;
 use crate::point::Point;
 use spade::{DelaunayTriangulation, Triangulation};
 
-/// Interpolates to the grid using the Nearest Neighbor method.
-///
-/// # Arguments
-/// `grid` - The grid to interpolate to.
-/// `points` - The points to interpolate from.
+/// The Natural Neighbor method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NaturalNeighbor;
+
+impl Interpolator for NaturalNeighbor {
+    fn predict(
+        &self,
+        points: &[Point],
+        locations: &[(f64, f64)],
+    ) -> Result<Vec<Option<f64>>, Box<dyn Error + Send + Sync>> {
+        interpolate_locations(points, locations.iter().copied())
+    }
+}
+
+/// Interpolate the value at each location from the natural neighbors of the location,
+/// or None for locations outside of the convex hull of the points.
 ///
 /// # Errors
 /// Returns an error if the triangulation fails.
-pub fn interpolate(grid: &mut Grid, points: &[Point]) -> Result<(), Box<dyn Error>> {
+fn interpolate_locations(
+    points: &[Point],
+    locations: impl Iterator<Item = (f64, f64)>,
+) -> Result<Vec<Option<f64>>, Box<dyn Error + Send + Sync>> {
     let triangulation: DelaunayTriangulation<Point> =
         DelaunayTriangulation::bulk_load(points.to_vec())?;
 
@@ -20,16 +35,41 @@ pub fn interpolate(grid: &mut Grid, points: &[Point]) -> Result<(), Box<dyn Erro
 
     let gradient_point = [0., 0.];
 
-    grid.iter_world_mut().for_each(|(x, y, grid_value)| {
-        if let Some(interpolated_value) = interpolator.interpolate_gradient(
-            |v| v.data().values[0],
-            |_| gradient_point,
-            1.,
-            (x, y).into(),
-        ) {
-            *grid_value = interpolated_value;
-        }
-    });
+    Ok(locations
+        .map(|(x, y)| {
+            interpolator.interpolate_gradient(
+                |v| v.data().values[0],
+                |_| gradient_point,
+                1.,
+                (x, y).into(),
+            )
+        })
+        .collect())
+}
+
+/// Interpolates to the grid using the Natural Neighbor method.
+///
+/// # Arguments
+/// `grid` - The grid to interpolate to.
+/// `points` - The points to interpolate from.
+///
+/// # Errors
+/// Returns an error if the triangulation fails.
+pub fn interpolate(grid: &mut Grid, points: &[Point]) -> Result<(), Box<dyn Error>> {
+    let locations = grid
+        .x()
+        .into_iter()
+        .copied()
+        .zip(grid.y().into_iter().copied());
+    let values = interpolate_locations(points, locations).map_err(|err| err as Box<dyn Error>)?;
+
+    grid.iter_world_mut()
+        .zip(values)
+        .for_each(|((_, _, grid_value), value)| {
+            if let Some(interpolated_value) = value {
+                *grid_value = interpolated_value;
+            }
+        });
 
     Ok(())
 }
@@ -1,18 +1,65 @@
 use std::error::Error;
 
+use crate::bounds::Bounds;
 use crate::grid::Grid;
+use crate::interpolate::{Interpolator, PointTree};
 use crate::point::Point;
-use geo::{LineString, Polygon};
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use geo::{Area, BooleanOps, BoundingRect, LineString, MultiPolygon, Polygon, Rect};
+use rayon::iter::{
+    IntoParallelIterator, IntoParallelRefIterator, ParallelBridge, ParallelIterator,
+};
 use std::convert::Into;
 use voronator::{delaunator, VoronoiDiagram};
 
-fn voronoi_to_grid(
-    voronoi: &VoronoiDiagram<delaunator::Point>,
-    polygon_labels: &[f64],
-    grid: &mut Grid,
-) -> Result<(), Box<dyn Error>> {
-    let polygons = voronoi
+/// The Nearest Neighbor method with its parameters.
+///
+/// See `interpolate` for a description of the parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NearestNeighbor {
+    pub max_distance: Option<f64>,
+}
+
+impl Interpolator for NearestNeighbor {
+    fn predict(
+        &self,
+        points: &[Point],
+        locations: &[(f64, f64)],
+    ) -> Result<Vec<Option<f64>>, Box<dyn Error + Send + Sync>> {
+        if points.is_empty() {
+            return Err("No points to interpolate".into());
+        }
+
+        let point_tree = PointTree::from_points(points);
+        let max_distance_squared = self
+            .max_distance
+            .map_or(f64::INFINITY, |distance| distance.powi(2));
+        Ok(locations
+            .par_iter()
+            .map(|(x, y)| {
+                let nearest = point_tree.nearest_one(&[*x, *y]);
+                (nearest.distance <= max_distance_squared)
+                    .then(|| points[nearest.item as usize].values[0])
+            })
+            .collect())
+    }
+}
+
+fn build_voronoi(
+    points: &[Point],
+    bounds: &Bounds,
+) -> Result<VoronoiDiagram<delaunator::Point>, Box<dyn Error>> {
+    if points.is_empty() {
+        return Err("No points to interpolate".into());
+    }
+
+    let voronoi_points: Vec<_> = points.par_iter().map(Into::into).collect();
+    let (left, bottom, right, top) = bounds.clone().into();
+    VoronoiDiagram::from_tuple(&(left, bottom), &(right, top), &voronoi_points)
+        .ok_or_else(|| "Error building voronoi diagram".into())
+}
+
+fn voronoi_to_polygons(voronoi: &VoronoiDiagram<delaunator::Point>) -> Vec<Polygon> {
+    voronoi
         .cells()
         .par_iter()
         .map(|cell| {
@@ -23,30 +70,230 @@ fn voronoi_to_grid(
                 .collect::<LineString>();
             Polygon::new(exterior, vec![])
         })
-        .collect::<Vec<Polygon>>();
+        .collect::<Vec<Polygon>>()
+}
 
-    grid.rasterize_polygons(&polygons, polygon_labels)
+fn voronoi_to_grid(
+    voronoi: &VoronoiDiagram<delaunator::Point>,
+    polygon_labels: &[f64],
+    grid: &mut Grid,
+) -> Result<(), Box<dyn Error>> {
+    grid.rasterize_polygons(&voronoi_to_polygons(voronoi), polygon_labels)
+}
+
+/// Set cells farther than `max_distance` from every point to nodata.
+fn mask_beyond_distance(grid: &mut Grid, point_tree: &PointTree<2>, max_distance: f64) {
+    let nodata = grid.nodata();
+    let max_distance_squared = max_distance.powi(2);
+    grid.iter_world_mut()
+        .par_bridge()
+        .for_each(|(x, y, grid_value)| {
+            let nearest = point_tree.nearest_one(&[x, y]);
+            if nearest.distance > max_distance_squared {
+                *grid_value = nodata;
+            }
+        });
 }
 
 /// Interpolates to the grid using the Nearest Neighbor method.
 ///
+/// Cells are filled by rasterizing the Voronoi diagram of the points.
+///
+/// # Arguments
+/// `grid` - The grid to interpolate to.
+/// `points` - The points to interpolate from.
+///
 /// # Errors
-/// Returns an error if there are no points to interpolate.
+/// Returns an error if there are no points to interpolate or the Voronoi diagram cannot be built.
 pub fn interpolate(grid: &mut Grid, points: &[Point]) -> Result<(), Box<dyn Error>> {
+    let voronoi = build_voronoi(points, &grid.bounds())?;
+    let polygon_labels: Vec<_> = points.par_iter().map(|point| point.values[0]).collect();
+    voronoi_to_grid(&voronoi, &polygon_labels, grid)
+}
+
+/// Interpolates to the grid using the Nearest Neighbor method, as `interpolate`,
+/// leaving cells farther than `max_distance` from every point as nodata.
+///
+/// # Arguments
+/// `grid` - The grid to interpolate to.
+/// `points` - The points to interpolate from.
+/// `max_distance` - Cells farther than this distance from every point are left as nodata.
+///
+/// # Errors
+/// Returns an error if there are no points to interpolate or the Voronoi diagram cannot be built.
+pub fn interpolate_within_distance(
+    grid: &mut Grid,
+    points: &[Point],
+    max_distance: f64,
+) -> Result<(), Box<dyn Error>> {
+    interpolate(grid, points)?;
+    mask_beyond_distance(grid, &PointTree::from_points(points), max_distance);
+    Ok(())
+}
+
+/// A band written by `allocate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Output {
+    /// The value of the nearest point.
+    Value,
+    /// The index of the nearest point in the input slice.
+    Index,
+    /// The distance to the nearest point.
+    Distance,
+}
+
+/// Interpolates to the grid using the Nearest Neighbor method.
+///
+/// Cells are filled by querying a kd-tree of the points, so no Voronoi diagram is built.
+/// This handles degenerate inputs, such as duplicate or collinear points, that the Voronoi method cannot,
+/// with cells nearest to several points at one position taking the value of any one of them.
+///
+/// # Arguments
+/// `grid` - The grid to interpolate to.
+/// `points` - The points to interpolate from.
+/// `max_distance` - Cells farther than this distance from every point are left as nodata.
+///
+/// # Errors
+/// Returns an error if there are no points to interpolate.
+pub fn interpolate_kd_tree(
+    grid: &mut Grid,
+    points: &[Point],
+    max_distance: Option<f64>,
+) -> Result<(), Box<dyn Error>> {
+    allocate(grid, points, max_distance, &[Output::Value])
+}
+
+/// Allocates each grid cell to its nearest point (Thiessen allocation).
+///
+/// The grid is reset to one band per output, in the order given.
+/// Cells farther than `max_distance` from every point are left as nodata in every band.
+///
+/// # Arguments
+/// `grid` - The grid to write to.
+/// `points` - The points to allocate cells to.
+/// `max_distance` - Cells farther than this distance from every point are left as nodata.
+/// `outputs` - The bands to write.
+///
+/// # Errors
+/// Returns an error if there are no points to allocate cells to.
+pub fn allocate(
+    grid: &mut Grid,
+    points: &[Point],
+    max_distance: Option<f64>,
+    outputs: &[Output],
+) -> Result<(), Box<dyn Error>> {
     if points.is_empty() {
         return Err("No points to interpolate".into());
     }
 
-    let voronoi_points: Vec<_> = points.par_iter().map(Into::into).collect();
-    let polygon_labels: Vec<_> = points.par_iter().map(|point| point.values[0]).collect();
+    let point_tree = PointTree::from_points(points);
+    let max_distance_squared = max_distance.map_or(f64::INFINITY, |distance| distance.powi(2));
 
-    let (left, bottom, right, top) = grid.bounds().into();
-    if let Some(voronoi) =
-        VoronoiDiagram::from_tuple(&(left, bottom), &(right, top), &voronoi_points)
-    {
-        voronoi_to_grid(&voronoi, &polygon_labels, grid)?;
-        Ok(())
-    } else {
-        Err("Error building voronoi diagram".into())
+    grid.reset_bands(outputs.len());
+
+    grid.iter_world_bands_mut()
+        .par_bridge()
+        .for_each(|(x, y, mut grid_values)| {
+            let nearest = point_tree.nearest_one(&[x, y]);
+            if nearest.distance > max_distance_squared {
+                return;
+            }
+            outputs
+                .iter()
+                .zip(grid_values.iter_mut())
+                .for_each(|(output, grid_value)| {
+                    *grid_value = match output {
+                        Output::Value => points[nearest.item as usize].values[0],
+                        Output::Index => nearest.item as f64,
+                        Output::Distance => nearest.distance.sqrt(),
+                    };
+                });
+        });
+    Ok(())
+}
+
+/// Builds the Voronoi (Thiessen) polygon of each point, clipped to the bounds.
+///
+/// Polygons are returned in the same order as the points.
+/// Points whose polygon does not overlap the bounds get an empty polygon.
+///
+/// # Errors
+/// Returns an error if there are no points or the Voronoi diagram cannot be built.
+pub fn voronoi_polygons(points: &[Point], bounds: &Bounds) -> Result<Vec<Polygon>, Box<dyn Error>> {
+    // The diagram is built over padded bounds and clipped afterwards,
+    // since cells near the edge of the diagram are distorted by its helper points.
+    let (left, bottom, right, top) = bounds.clone().into();
+    let (width, height) = (right - left, top - bottom);
+    let padded_bounds = Bounds::new(left - width, bottom - height, right + width, top + height)?;
+    let clip = MultiPolygon::new(vec![Rect::new((left, bottom), (right, top)).to_polygon()]);
+
+    Ok(voronoi_to_polygons(&build_voronoi(points, &padded_bounds)?)
+        .into_par_iter()
+        .map(|polygon| {
+            MultiPolygon::new(vec![polygon])
+                .intersection(&clip)
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| Polygon::new(LineString::new(vec![]), vec![]))
+        })
+        .collect())
+}
+
+/// Builds the Voronoi (Thiessen) polygon of each point, clipped to a region.
+///
+/// Polygons are returned in the same order as the points.
+/// Points whose polygon does not overlap the region get an empty `MultiPolygon`.
+///
+/// # Errors
+/// Returns an error if there are no points or the Voronoi diagram cannot be built.
+pub fn clipped_voronoi_polygons(
+    points: &[Point],
+    region: &MultiPolygon,
+) -> Result<Vec<MultiPolygon>, Box<dyn Error>> {
+    let bounds = bounds_containing(points, region)?;
+    Ok(voronoi_polygons(points, &bounds)?
+        .par_iter()
+        .map(|polygon| MultiPolygon::new(vec![polygon.clone()]).intersection(region))
+        .collect())
+}
+
+/// Calculates the fraction of a region's area that is closest to each point (Thiessen weights).
+///
+/// Weights are returned in the same order as the points and sum to 1.
+///
+/// # Errors
+/// Returns an error if there are no points, the region has no area, or the Voronoi diagram cannot be built.
+pub fn thiessen_weights(
+    points: &[Point],
+    region: &MultiPolygon,
+) -> Result<Vec<f64>, Box<dyn Error>> {
+    let region_area = region.unsigned_area();
+    if region_area == 0. {
+        return Err("Region has no area".into());
     }
+    Ok(clipped_voronoi_polygons(points, region)?
+        .iter()
+        .map(|polygon| polygon.unsigned_area() / region_area)
+        .collect())
+}
+
+/// Get bounds that contain all points and the region.
+fn bounds_containing(points: &[Point], region: &MultiPolygon) -> Result<Bounds, Box<dyn Error>> {
+    let (mut left, mut bottom, mut right, mut top) = region.bounding_rect().map_or(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |rect| (rect.min().x, rect.min().y, rect.max().x, rect.max().y),
+    );
+    points.iter().for_each(|point| {
+        left = left.min(point.x);
+        bottom = bottom.min(point.y);
+        right = right.max(point.x);
+        top = top.max(point.y);
+    });
+    let margin = (right - left).max(top - bottom).max(1.) * 0.01;
+    Bounds::new(left - margin, bottom - margin, right + margin, top + margin)
 }
@@ -0,0 +1,102 @@
+use std::error::Error;
+
+use crate::grid::Grid;
+use crate::interpolate::inverse_distance_weighting::calculate_interpolated_value;
+use crate::interpolate::{Interpolator, PointTree};
+use crate::point::Point;
+use crate::Timestamp;
+use rayon::prelude::*;
+
+/// The space-time Inverse Distance Weighting method with its parameters.
+///
+/// See `interpolate` for a description of the parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpaceTimeInverseDistanceWeighting {
+    pub time: Timestamp,
+    pub time_scale: f64,
+    pub power: f64,
+    pub radius: f64,
+    pub min_neighbors: usize,
+}
+
+impl Interpolator for SpaceTimeInverseDistanceWeighting {
+    fn predict(
+        &self,
+        points: &[Point],
+        locations: &[(f64, f64)],
+    ) -> Result<Vec<Option<f64>>, Box<dyn Error + Send + Sync>> {
+        let point_tree = build_point_tree(points, self.time, self.time_scale)?;
+        let radius_squared = self.radius.powi(2);
+        Ok(locations
+            .par_iter()
+            .map(|(x, y)| {
+                let neighbors = point_tree.within(&[*x, *y, 0.], radius_squared);
+                (!neighbors.is_empty() && neighbors.len() >= self.min_neighbors)
+                    .then(|| calculate_interpolated_value(&neighbors, points, self.power))
+            })
+            .collect())
+    }
+}
+
+/// Build a tree of the points in space and scaled time, relative to `time`.
+fn build_point_tree(
+    points: &[Point],
+    time: Timestamp,
+    time_scale: f64,
+) -> Result<PointTree<3>, Box<dyn Error + Send + Sync>> {
+    if !(time_scale.is_finite() && time_scale >= 0.) {
+        return Err("Time scale must be non-negative and finite".into());
+    }
+    let positions = points
+        .iter()
+        .map(|point| {
+            let point_time = point.time.ok_or("Every point must have a time")?;
+            let offset = (point_time - time) as f64 * time_scale;
+            Ok([point.x, point.y, offset])
+        })
+        .collect::<Result<_, Box<dyn Error + Send + Sync>>>()?;
+    Ok(PointTree::new(positions))
+}
+
+/// Interpolates to the grid at a point in time using the space-time Inverse Distance Weighting method.
+///
+/// Points are weighted by their distance in space and time combined,
+/// `sqrt(dx ^ 2 + dy ^ 2 + (time_scale * dt) ^ 2)`,
+/// so a point observed `dt` seconds from `time` counts as `time_scale * dt` world units further away.
+///
+/// # Arguments
+/// `grid` - The grid to interpolate to.
+/// `points` - The points to interpolate from. Every point must have a time.
+/// `time` - The time the interpolated values are valid at.
+/// `time_scale` - The world units equivalent to one second. Zero ignores time.
+/// `power` - The power used in the interpolation. Values are weighted by 1 / distance ^ power.
+/// `radius` - The combined distance to search for points to interpolate from for each grid point. Points outside of this radius are ignored,
+/// so points more than `radius / time_scale` seconds from `time` are never used.
+/// `min_neighbors` - The minimum number of points in radius required to interpolate a value.
+///
+/// # Errors
+/// Returns an error if a point has no time or `time_scale` is negative or not finite.
+pub fn interpolate(
+    grid: &mut Grid,
+    points: &[Point],
+    time: Timestamp,
+    time_scale: f64,
+    power: f64,
+    radius: f64,
+    min_neighbors: usize,
+) -> Result<(), Box<dyn Error>> {
+    let point_tree =
+        build_point_tree(points, time, time_scale).map_err(|err| err as Box<dyn Error>)?;
+
+    let radius_squared = radius.powi(2);
+
+    grid.iter_world_mut()
+        .par_bridge()
+        .for_each(|(x, y, grid_value)| {
+            let neighbors = point_tree.within(&[x, y, 0.], radius_squared);
+            if !neighbors.is_empty() && neighbors.len() >= min_neighbors {
+                *grid_value = calculate_interpolated_value(&neighbors, points, power);
+            }
+        });
+    Ok(())
+}
@@ -1,10 +1,17 @@
 use euclid::approxeq::ApproxEq;
 
 pub mod bounds;
+pub mod cell_value;
+pub mod cross_validation;
 pub mod draw;
 pub mod grid;
 pub mod interpolate;
 pub mod point;
+pub mod resolution;
+mod rings;
+
+/// A point in time, in seconds since the Unix epoch in UTC.
+pub type Timestamp = i64;
 
 /// Test if two f64s are equivalent.
 ///
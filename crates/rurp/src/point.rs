@@ -1,7 +1,11 @@
+use crate::Timestamp;
+
 #[derive(Clone, Debug)]
 pub struct Point {
     pub x: f64,
     pub y: f64,
+    /// The time of the observation, if known.
+    pub time: Option<Timestamp>,
     pub values: ndarray::Array1<f64>,
 }
 
@@ -12,9 +16,19 @@ impl Point {
         Point {
             x,
             y,
+            time: None,
             values: ndarray::Array1::from_vec(values),
         }
     }
+
+    /// Create a new Point instance observed at `time`.
+    #[must_use]
+    pub fn new_at_time(x: f64, y: f64, time: Timestamp, values: Vec<f64>) -> Self {
+        Point {
+            time: Some(time),
+            ..Point::new(x, y, values)
+        }
+    }
 }
 
 impl spade::HasPosition for Point {
@@ -0,0 +1,95 @@
+use std::error::Error;
+
+/// The size of a grid cell in world units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Resolution {
+    x: f64,
+    y: f64,
+}
+
+impl Resolution {
+    /// Get the cell width.
+    #[must_use]
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+    /// Get the cell height.
+    #[must_use]
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+}
+
+impl Resolution {
+    /// Create a new Resolution instance with separate cell width and height.
+    /// # Errors
+    /// Returns an error if either cell size is not positive and finite.
+    pub fn new(x: f64, y: f64) -> Result<Self, Box<dyn Error>> {
+        if !(x.is_finite() && x > 0.) {
+            return Err("x resolution must be positive and finite".into());
+        }
+        if !(y.is_finite() && y > 0.) {
+            return Err("y resolution must be positive and finite".into());
+        }
+        Ok(Self { x, y })
+    }
+
+    /// Create a new Resolution instance with square cells.
+    /// # Errors
+    /// Returns an error if the cell size is not positive and finite.
+    pub fn square(size: f64) -> Result<Self, Box<dyn Error>> {
+        Resolution::new(size, size)
+    }
+}
+
+impl TryFrom<f64> for Resolution {
+    type Error = Box<dyn Error>;
+    /// Convert a cell size into a square Resolution instance.
+    /// # Errors
+    /// Returns an error if the cell size is not positive and finite.
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Resolution::square(value)
+    }
+}
+
+impl TryFrom<(f64, f64)> for Resolution {
+    type Error = Box<dyn Error>;
+    /// Convert a tuple of cell width and height into a Resolution instance.
+    /// # Errors
+    /// Returns an error if either cell size is not positive and finite.
+    fn try_from(value: (f64, f64)) -> Result<Self, Self::Error> {
+        Resolution::new(value.0, value.1)
+    }
+}
+
+/// How a grid is fit to bounds that are not an exact multiple of the resolution.
+///
+/// Grids are anchored at the left and bottom bounds; only the right and top bounds move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtentPolicy {
+    /// Add a partial column or row, expanding the bounds to the next whole cell.
+    Expand,
+    /// Drop the partial column or row, shrinking the bounds to the last whole cell.
+    Shrink,
+    /// Return an error.
+    Exact,
+}
+
+impl ExtentPolicy {
+    /// Get the number of cells of `size` to fit along a world space `extent`.
+    ///
+    /// Extents within a relative tolerance of a whole number of cells are treated as exact.
+    pub(crate) fn cell_count(self, extent: f64, size: f64) -> Result<usize, Box<dyn Error>> {
+        let cells = extent / size;
+        let rounded = cells.round();
+        if (cells - rounded).abs() <= 1e-9 * rounded.max(1.) {
+            return Ok(rounded as usize);
+        }
+        match self {
+            ExtentPolicy::Expand => Ok(cells.ceil() as usize),
+            ExtentPolicy::Shrink if cells >= 1. => Ok(cells.floor() as usize),
+            ExtentPolicy::Shrink => Err("bounds are smaller than one cell".into()),
+            ExtentPolicy::Exact => Err("bounds are not an exact multiple of the resolution".into()),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use geo::coordinate_position::CoordPos;
+use geo::{Area, BoundingRect, Coord, CoordinatePosition, LineString, MultiPolygon, Polygon};
+
+/// Get the boundary of a region made of pieces, as directed edges.
+///
+/// Each piece is a ring of nodes, without repeating the first node, with the region on its left.
+/// Edges shared by two neighboring pieces run in opposite directions and cancel out,
+/// leaving the edges on the boundary of the region.
+pub(crate) fn boundary_edges<N, I>(pieces: I) -> Vec<(N, N)>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = Vec<N>>,
+{
+    let mut edges = HashSet::new();
+    let mut order = vec![];
+    for piece in pieces {
+        for idx in 0..piece.len() {
+            let edge = (piece[idx], piece[(idx + 1) % piece.len()]);
+            if !edges.remove(&(edge.1, edge.0)) && edges.insert(edge) {
+                order.push(edge);
+            }
+        }
+    }
+    // Keep the order the edges were found in, so the output does not depend on hashing.
+    order.retain(|edge| edges.remove(edge));
+    order
+}
+
+/// Assemble the boundary edges of a region into polygons.
+///
+/// The edges are directed with the region on their left, in a space where positive areas are
+/// counterclockwise. Counterclockwise rings are exteriors and clockwise rings are holes,
+/// each placed in the smallest exterior containing it. Where the boundary touches itself at
+/// a node, it is split into rings that touch there instead of one ring that passes it twice.
+pub(crate) fn polygons_from_edges<N>(
+    edges: &[(N, N)],
+    coord: impl Fn(N) -> Coord<f64>,
+) -> MultiPolygon<f64>
+where
+    N: Copy + Eq + Hash,
+{
+    let mut outgoing: HashMap<N, Vec<N>> = HashMap::new();
+    for &(start, end) in edges {
+        outgoing.entry(start).or_default().push(end);
+    }
+
+    let mut rings = vec![];
+    for &(first, _) in edges {
+        while let Some(mut current) = outgoing.get_mut(&first).and_then(Vec::pop) {
+            let mut ring = vec![first, current];
+            while current != first {
+                let Some(next) = outgoing.get_mut(&current).and_then(Vec::pop) else {
+                    break;
+                };
+                ring.push(next);
+                current = next;
+            }
+            if current == first {
+                rings.extend(
+                    simple_rings(ring)
+                        .into_iter()
+                        .filter(|ring| ring.len() > 3)
+                        .map(|ring| ring.into_iter().map(&coord).collect::<LineString<f64>>()),
+                );
+            }
+        }
+    }
+
+    let (exteriors, holes): (Vec<LineString<f64>>, Vec<LineString<f64>>) = rings
+        .into_iter()
+        .partition(|ring| Polygon::new(ring.clone(), vec![]).signed_area() > 0.);
+    let mut polygons: Vec<Polygon<f64>> = exteriors
+        .into_iter()
+        .map(|exterior| Polygon::new(exterior, vec![]))
+        .collect();
+    let areas: Vec<f64> = polygons.iter().map(Area::unsigned_area).collect();
+    let bounding_rects: Vec<_> = polygons.iter().map(BoundingRect::bounding_rect).collect();
+
+    for hole in holes {
+        let hole_rect = hole.bounding_rect();
+        let container = (0..polygons.len())
+            .filter(|&idx| match (bounding_rects[idx], hole_rect) {
+                (Some(outer), Some(inner)) => {
+                    outer.min().x <= inner.min().x
+                        && outer.min().y <= inner.min().y
+                        && outer.max().x >= inner.max().x
+                        && outer.max().y >= inner.max().y
+                }
+                _ => false,
+            })
+            .filter(|&idx| {
+                // Holes may touch their exterior at nodes, so decide by the first node that does not.
+                hole.coords()
+                    .map(|coord| polygons[idx].coordinate_position(coord))
+                    .find(|position| *position != CoordPos::OnBoundary)
+                    == Some(CoordPos::Inside)
+            })
+            .min_by(|&a, &b| areas[a].total_cmp(&areas[b]));
+        if let Some(idx) = container {
+            polygons[idx].interiors_push(hole);
+        }
+    }
+    MultiPolygon::new(polygons)
+}
+
+/// Split a closed ring of nodes at every node it passes more than once,
+/// into closed rings that each pass every node once.
+fn simple_rings<N: Copy + Eq + Hash>(ring: Vec<N>) -> Vec<Vec<N>> {
+    let mut rings = vec![];
+    let mut path: Vec<N> = vec![];
+    let mut positions: HashMap<N, usize> = HashMap::new();
+    for node in ring {
+        if let Some(&position) = positions.get(&node) {
+            let mut loop_nodes = path.split_off(position + 1);
+            loop_nodes.iter().for_each(|node| {
+                positions.remove(node);
+            });
+            loop_nodes.insert(0, node);
+            loop_nodes.push(node);
+            rings.push(loop_nodes);
+        } else {
+            positions.insert(node, path.len());
+            path.push(node);
+        }
+    }
+    rings
+}
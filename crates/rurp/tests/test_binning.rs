@@ -0,0 +1,78 @@
+use rstest::rstest;
+use rurp::interpolate::binning::{interpolate, Aggregation};
+use rurp::point::Point;
+pub mod utils;
+use utils::{build_empty_grid, build_stub_points, STUB_BOUNDS};
+
+fn build_cell_points() -> Vec<Point> {
+    vec![
+        Point::new(10.2, 20.7, vec![4.]),
+        Point::new(10.9, 20.1, vec![-2.]),
+        Point::new(10.5, 20.5, vec![7.]),
+        Point::new(55.5, 60.5, vec![1.]),
+        Point::new(100., 100., vec![9.]),
+        Point::new(-1., 50., vec![1000.]),
+    ]
+}
+
+#[rstest]
+#[case(Aggregation::Mean, 3.)]
+#[case(Aggregation::Min, -2.)]
+#[case(Aggregation::Max, 7.)]
+#[case(Aggregation::Count, 3.)]
+#[case(Aggregation::Sum, 9.)]
+#[case(Aggregation::Last, 7.)]
+fn test_aggregation(#[case] aggregation: Aggregation, #[case] expected: f64) {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+
+    interpolate(&mut grid, &build_cell_points(), &[aggregation]);
+
+    assert_eq!(grid.bands(), 1);
+    assert_eq!(grid.data()[[20, 10, 0]], expected);
+}
+
+#[rstest]
+fn test_empty_cells_and_edges() {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+
+    interpolate(
+        &mut grid,
+        &build_cell_points(),
+        &[Aggregation::Sum, Aggregation::Count],
+    );
+
+    assert_eq!(grid.bands(), 2);
+    assert!(grid.data()[[0, 0, 0]].is_nan());
+    assert_eq!(grid.data()[[0, 0, 1]], 0.);
+    assert_eq!(grid.data()[[60, 55, 0]], 1.);
+    assert_eq!(grid.data()[[99, 99, 0]], 9.);
+    assert_eq!(grid.data().index_axis(ndarray::Axis(2), 1).sum(), 5.);
+}
+
+#[rstest]
+fn test_last_follows_input_order() {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 10., f64::NAN);
+    let points: Vec<Point> = (0..10_000)
+        .map(|idx| Point::new(5., 5., vec![idx as f64]))
+        .collect();
+
+    interpolate(&mut grid, &points, &[Aggregation::Last, Aggregation::Count]);
+
+    assert_eq!(grid.data()[[0, 0, 0]], 9_999.);
+    assert_eq!(grid.data()[[0, 0, 1]], 10_000.);
+}
+
+#[rstest]
+#[case(1, Aggregation::Mean)]
+#[case(2, Aggregation::Count)]
+fn test_interpolate(#[case] case_number: usize, #[case] aggregation: Aggregation) {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 5., f64::NAN);
+    let points = build_stub_points(&STUB_BOUNDS, &10_000);
+
+    interpolate(&mut grid, &points, &[aggregation]);
+
+    utils::assert_grid_matches_snapshot(
+        &grid,
+        &format!("test_interpolate_binning_{}", case_number),
+    );
+}
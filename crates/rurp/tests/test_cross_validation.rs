@@ -0,0 +1,173 @@
+use rstest::rstest;
+use rurp::cross_validation::{k_fold, leave_one_out};
+use rurp::interpolate::inverse_distance_weighting::InverseDistanceWeighting;
+use rurp::interpolate::inverse_distance_weighting_global::InverseDistanceWeightingGlobal;
+use rurp::interpolate::moving_window::{MovingWindow, Neighborhood, Statistic};
+use rurp::interpolate::natural_neighbor::NaturalNeighbor;
+use rurp::interpolate::nearest_neighbor::NearestNeighbor;
+use rurp::interpolate::Interpolator;
+use rurp::point::Point;
+pub mod utils;
+use utils::{build_stub_points, STUB_BOUNDS};
+
+fn build_plane_points() -> Vec<Point> {
+    (0..10)
+        .flat_map(|i| (0..10).map(move |j| (f64::from(i) * 10. + 5., f64::from(j) * 10. + 5.)))
+        .map(|(x, y)| Point::new(x, y, vec![2. * x + y]))
+        .collect()
+}
+
+#[rstest]
+#[case(&InverseDistanceWeighting { power: 2., radius: 25., min_neighbors: 0 })]
+#[case(&InverseDistanceWeightingGlobal { power: 2. })]
+#[case(&MovingWindow { statistic: Statistic::Mean, neighborhood: Neighborhood::Nearest(4), min_neighbors: 0 })]
+#[case(&NaturalNeighbor)]
+#[case(&NearestNeighbor { max_distance: None })]
+fn test_leave_one_out(#[case] interpolator: &dyn Interpolator) {
+    let points = build_stub_points(&STUB_BOUNDS, &100);
+
+    let validation = leave_one_out(interpolator, &points).unwrap();
+
+    assert_eq!(
+        validation.residuals().len() + validation.unpredicted().len(),
+        points.len()
+    );
+    assert!(validation.mae() > 0.);
+    assert!(validation.rmse() >= validation.mae());
+    assert!(validation.bias().abs() <= validation.mae());
+    assert!(validation.r_squared() <= 1.);
+    for residual in validation.residuals() {
+        assert_eq!(residual.observed, points[residual.index].values[0]);
+    }
+}
+
+#[rstest]
+#[case(&InverseDistanceWeighting { power: 2., radius: 25., min_neighbors: 0 })]
+#[case(&InverseDistanceWeightingGlobal { power: 2. })]
+fn test_idw_exact_matches(#[case] interpolator: &dyn Interpolator) {
+    let points = vec![
+        Point::new(10., 10., vec![1.]),
+        Point::new(10., 10., vec![3.]),
+        Point::new(20., 20., vec![10.]),
+        Point::new(20., 20., vec![10.]),
+    ];
+
+    // Locations at points take the mean of the points there.
+    let predicted = interpolator
+        .predict(&points, &[(10., 10.), (20., 20.)])
+        .unwrap();
+    assert_eq!(predicted, vec![Some(2.), Some(10.)]);
+
+    // Each duplicate station is predicted exactly by its twin.
+    let validation = leave_one_out(interpolator, &points).unwrap();
+    assert!(validation.unpredicted().is_empty());
+    let predicted: Vec<f64> = validation
+        .residuals()
+        .iter()
+        .map(|residual| residual.predicted)
+        .collect();
+    assert_eq!(predicted, vec![3., 1., 10., 10.]);
+}
+
+#[rstest]
+fn test_natural_neighbor_reproduces_plane() {
+    let points = build_plane_points();
+
+    let validation = leave_one_out(&NaturalNeighbor, &points).unwrap();
+
+    // Only the corners fall outside the convex hull of the remaining points.
+    assert_eq!(validation.unpredicted(), &[0, 9, 90, 99]);
+    assert!(validation.mae() < 1e-9);
+    assert!(validation.bias().abs() < 1e-9);
+    assert!((validation.r_squared() - 1.).abs() < 1e-9);
+}
+
+#[rstest]
+fn test_metrics() {
+    let points = vec![
+        Point::new(0., 0., vec![1.]),
+        Point::new(1., 0., vec![3.]),
+        Point::new(100., 0., vec![5.]),
+    ];
+
+    let validation = leave_one_out(
+        &NearestNeighbor {
+            max_distance: Some(10.),
+        },
+        &points,
+    )
+    .unwrap();
+
+    assert_eq!(validation.unpredicted(), &[2]);
+    assert_eq!(validation.residuals().len(), 2);
+    assert_eq!(validation.residuals()[0].residual(), 2.);
+    assert_eq!(validation.residuals()[1].residual(), -2.);
+    assert_eq!(validation.mae(), 2.);
+    assert_eq!(validation.rmse(), 2.);
+    assert_eq!(validation.bias(), 0.);
+    assert_eq!(validation.r_squared(), -3.);
+}
+
+#[rstest]
+#[case(2)]
+#[case(5)]
+#[case(10)]
+fn test_k_fold(#[case] folds: usize) {
+    let points = build_plane_points();
+    let interpolator = InverseDistanceWeighting {
+        power: 2.,
+        radius: 30.,
+        min_neighbors: 1,
+    };
+
+    let validation = k_fold(&interpolator, &points, folds).unwrap();
+
+    assert!(validation.unpredicted().is_empty());
+    let indices: Vec<usize> = validation.residuals().iter().map(|r| r.index).collect();
+    assert_eq!(indices, (0..points.len()).collect::<Vec<_>>());
+    assert!(validation.r_squared() > 0.9);
+}
+
+#[rstest]
+#[case(1, "At least two folds are required")]
+#[case(101, "There must be at least as many points as folds")]
+fn test_k_fold_errors(#[case] folds: usize, #[case] expected_error: &str) {
+    let points = build_plane_points();
+
+    let result = k_fold(&NearestNeighbor::default(), &points, folds);
+
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains(expected_error));
+}
+
+#[derive(Debug)]
+struct PredictError;
+
+impl std::fmt::Display for PredictError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Prediction failed")
+    }
+}
+
+impl std::error::Error for PredictError {}
+
+struct FailingInterpolator;
+
+impl Interpolator for FailingInterpolator {
+    fn predict(
+        &self,
+        _points: &[Point],
+        _locations: &[(f64, f64)],
+    ) -> Result<Vec<Option<f64>>, Box<dyn std::error::Error + Send + Sync>> {
+        Err(Box::new(PredictError))
+    }
+}
+
+#[rstest]
+fn test_k_fold_keeps_predict_error() {
+    let points = build_plane_points();
+
+    let error = k_fold(&FailingInterpolator, &points, 4).unwrap_err();
+
+    assert!(error.downcast_ref::<PredictError>().is_some());
+}
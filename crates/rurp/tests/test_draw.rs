@@ -3,10 +3,13 @@ use rurp::bounds::Bounds;
 use rurp::draw::write_grid_data;
 use rurp::grid::Grid;
 use std::env;
+pub mod utils;
+use utils::build_empty_grid;
 
 #[rstest]
 fn test_draw_a_grid() {
     let bounds = Bounds::new(0., 0., 500., 250.).unwrap();
+    #[allow(deprecated)]
     let mut grid = Grid::empty_from_bounds(&bounds, 1, f64::NAN).unwrap();
     grid.data_mut()
         .indexed_iter_mut()
@@ -19,3 +22,19 @@ fn test_draw_a_grid() {
     let _ = std::fs::remove_file(&file_path);
     write_grid_data(&grid, file_path.to_str().unwrap()).unwrap();
 }
+
+#[rstest]
+fn test_draw_a_u8_grid() {
+    let bounds = Bounds::new(0., 0., 256., 128.).unwrap();
+    let mut grid: Grid<u8> = build_empty_grid(&bounds, 1., 0);
+    grid.data_mut()
+        .indexed_iter_mut()
+        .for_each(|((y, x, _), value)| *value = (x + y).min(255) as u8);
+
+    let file_path = env::current_dir()
+        .unwrap()
+        .join("tests/snapshots")
+        .join("test_plot_a_grid_u8.png");
+    let _ = std::fs::remove_file(&file_path);
+    write_grid_data(&grid, file_path.to_str().unwrap()).unwrap();
+}
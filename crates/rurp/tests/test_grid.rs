@@ -1,10 +1,12 @@
 use geo::{coord, polygon};
 use rstest::rstest;
 use rurp::bounds::Bounds;
+use rurp::cell_value::CellValue;
 use rurp::equivalent;
-use rurp::grid::{Grid, ScreenSpace, WorldSpace};
+use rurp::grid::{Grid, Registration, ScreenSpace, WorldSpace};
+use rurp::resolution::{ExtentPolicy, Resolution};
 pub mod utils;
-use utils::{CONUS_BOUNDS, STUB_BOUNDS};
+use utils::{build_empty_grid, CONUS_BOUNDS, STUB_BOUNDS};
 
 #[rstest]
 #[case(4000, 710, 1351, f64::NAN)]
@@ -18,6 +20,7 @@ fn test_from_bounds(
 ) {
     let bounds = &*CONUS_BOUNDS;
 
+    #[allow(deprecated)]
     let grid = Grid::empty_from_bounds(bounds, resolution, nodata).unwrap();
 
     let (left, bottom, right, top) = bounds.clone().into();
@@ -25,15 +28,18 @@ fn test_from_bounds(
     assert_eq!(grid.width(), expected_width);
     assert_eq!(grid.height(), expected_height);
 
+    let half_cell_width = grid.cell_width() / 2.;
+    let half_cell_height = grid.cell_height() / 2.;
+
     assert!(equivalent(&grid.data()[[0, 0, 0]], &nodata));
-    assert_eq!(grid.x()[[0, 0]], left);
-    assert_eq!(grid.y()[[0, 0]], bottom);
+    assert!((grid.x()[[0, 0]] - (left + half_cell_width)).abs() < 1e-6);
+    assert!((grid.y()[[0, 0]] - (bottom + half_cell_height)).abs() < 1e-6);
 
     let max_y = expected_height - 1;
     let max_x = expected_width - 1;
     assert!(equivalent(&grid.data()[[max_y, max_x, 0]], &nodata));
-    assert_eq!(grid.x()[[max_y, max_x]], right);
-    assert_eq!(grid.y()[[max_y, max_x]], top);
+    assert!((grid.x()[[max_y, max_x]] - (right - half_cell_width)).abs() < 1e-6);
+    assert!((grid.y()[[max_y, max_x]] - (top - half_cell_height)).abs() < 1e-6);
 
     let mid_y = expected_height / 2;
     let mid_x = expected_width / 2;
@@ -42,6 +48,92 @@ fn test_from_bounds(
     assert!((grid.y()[[mid_y, mid_x]] - ((top + bottom) / 2.)).abs() < resolution as f64);
 }
 
+#[rstest]
+#[case([-125., 24., -66., 50.], (0.25, 0.25), 104, 236)]
+#[case([-125., 24., -66., 50.], (0.25, 0.5), 52, 236)]
+#[case([0., 0., 0.3, 0.7], (0.1, 0.1), 7, 3)]
+#[case([0., 0., 1_000., 500.], (2.5, 0.1), 5000, 400)]
+fn test_from_resolution(
+    #[case] bounds: [f64; 4],
+    #[case] resolution: (f64, f64),
+    #[case] expected_height: usize,
+    #[case] expected_width: usize,
+) {
+    let bounds: Bounds = bounds.try_into().unwrap();
+    let resolution: Resolution = resolution.try_into().unwrap();
+
+    let grid = Grid::empty_from_resolution(
+        &bounds,
+        &resolution,
+        ExtentPolicy::Exact,
+        Registration::PixelIsArea,
+        f64::NAN,
+    )
+    .unwrap();
+
+    assert_eq!(grid.height(), expected_height);
+    assert_eq!(grid.width(), expected_width);
+    assert!((grid.bounds().right() - bounds.right()).abs() < 1e-6);
+    assert!((grid.bounds().top() - bounds.top()).abs() < 1e-6);
+    assert!((grid.cell_width() - resolution.x()).abs() < 1e-9);
+    assert!((grid.cell_height() - resolution.y()).abs() < 1e-9);
+}
+
+#[rstest]
+#[case(ExtentPolicy::Expand, 11, 4, 11., 12.)]
+#[case(ExtentPolicy::Shrink, 10, 3, 10., 9.)]
+fn test_from_resolution_policy(
+    #[case] policy: ExtentPolicy,
+    #[case] expected_width: usize,
+    #[case] expected_height: usize,
+    #[case] expected_right: f64,
+    #[case] expected_top: f64,
+) {
+    let bounds = Bounds::new(0., 0., 10.5, 10.).unwrap();
+    let resolution = Resolution::new(1., 3.).unwrap();
+
+    let grid = Grid::empty_from_resolution(
+        &bounds,
+        &resolution,
+        policy,
+        Registration::PixelIsArea,
+        f64::NAN,
+    )
+    .unwrap();
+
+    assert_eq!(grid.width(), expected_width);
+    assert_eq!(grid.height(), expected_height);
+    assert_eq!(grid.bounds().left(), 0.);
+    assert_eq!(grid.bounds().bottom(), 0.);
+    assert_eq!(grid.bounds().right(), expected_right);
+    assert_eq!(grid.bounds().top(), expected_top);
+    assert_eq!(grid.cell_width(), 1.);
+    assert_eq!(grid.cell_height(), 3.);
+}
+
+#[rstest]
+#[case(ExtentPolicy::Exact, [0., 0., 10.5, 10.], "bounds are not an exact multiple of the resolution")]
+#[case(ExtentPolicy::Shrink, [0., 0., 0.5, 10.], "bounds are smaller than one cell")]
+fn test_from_resolution_policy_errors(
+    #[case] policy: ExtentPolicy,
+    #[case] bounds: [f64; 4],
+    #[case] expected_error: &str,
+) {
+    let bounds: Bounds = bounds.try_into().unwrap();
+    let resolution = Resolution::square(1.).unwrap();
+
+    let result = Grid::empty_from_resolution(
+        &bounds,
+        &resolution,
+        policy,
+        Registration::PixelIsArea,
+        f64::NAN,
+    );
+
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains(expected_error));
+}
+
 #[rstest]
 #[case(&*STUB_BOUNDS, 1, 100, 100)]
 #[case(&*CONUS_BOUNDS, 8000, 355, 676)]
@@ -51,6 +143,7 @@ fn test_properties(
     #[case] expected_height: usize,
     #[case] expected_width: usize,
 ) {
+    #[allow(deprecated)]
     let grid = Grid::empty_from_bounds(bounds, resolution, f64::NAN).unwrap();
 
     assert_eq!(grid.width(), expected_width);
@@ -69,6 +162,7 @@ fn test_transform(
     #[case] test_point_screen: euclid::Point2D<f64, ScreenSpace>,
     #[case] test_point_world: euclid::Point2D<f64, WorldSpace>,
 ) {
+    #[allow(deprecated)]
     let grid = Grid::empty_from_bounds(bounds, resolution, f64::NAN).unwrap();
 
     let s_w_transform = grid.screen_to_world_transform();
@@ -82,6 +176,114 @@ fn test_transform(
     assert_eq!(ws_transformed_point, test_point_screen);
 }
 
+#[rstest]
+#[case(Registration::PixelIsArea, [0., 0.], [0.5, 0.5], [99.5, 49.5])]
+#[case(Registration::PixelIsPoint, [-0.5, -0.5], [0., 0.], [100., 50.])]
+fn test_registration(
+    #[case] registration: Registration,
+    #[case] expected_origin: [f64; 2],
+    #[case] expected_first_center: [f64; 2],
+    #[case] expected_last_center: [f64; 2],
+) {
+    let bounds = Bounds::new(0., 0., 100., 50.).unwrap();
+    let resolution = Resolution::square(1.).unwrap();
+
+    let grid = Grid::empty_from_resolution(
+        &bounds,
+        &resolution,
+        ExtentPolicy::Exact,
+        registration,
+        f64::NAN,
+    )
+    .unwrap();
+
+    assert_eq!(grid.registration(), registration);
+    assert_eq!(grid.bounds(), bounds);
+    // The extent is that of the bounds for both registrations.
+    assert_eq!((grid.world_width(), grid.world_height()), (100., 50.));
+    let (max_y, max_x) = (grid.height() - 1, grid.width() - 1);
+    assert_eq!([grid.x()[[0, 0]], grid.y()[[0, 0]]], expected_first_center);
+    assert_eq!(
+        [grid.x()[[max_y, max_x]], grid.y()[[max_y, max_x]]],
+        expected_last_center
+    );
+    let origin = grid
+        .screen_to_world_transform()
+        .transform_point([0., 0.].into());
+    assert_eq!([origin.x, origin.y], expected_origin);
+}
+
+#[rstest]
+#[case(&*STUB_BOUNDS, 1, Registration::PixelIsArea)]
+#[case(&*STUB_BOUNDS, 3, Registration::PixelIsPoint)]
+#[case(&*CONUS_BOUNDS, 8000, Registration::PixelIsArea)]
+#[case(&*CONUS_BOUNDS, 8000, Registration::PixelIsPoint)]
+fn test_coordinates_match_transforms(
+    #[case] bounds: &Bounds,
+    #[case] resolution: usize,
+    #[case] registration: Registration,
+) {
+    let resolution = Resolution::square(resolution as f64).unwrap();
+    let grid = Grid::empty_from_resolution(
+        bounds,
+        &resolution,
+        ExtentPolicy::Expand,
+        registration,
+        f64::NAN,
+    )
+    .unwrap();
+    let tolerance = 1e-9 * resolution.x();
+
+    for ((i, j), &x) in grid.x().indexed_iter() {
+        let y = grid.y()[[i, j]];
+        let screen_point = grid
+            .world_to_screen_transform()
+            .transform_point([x, y].into());
+        assert!((screen_point.x - (j as f64 + 0.5)).abs() < 1e-9);
+        assert!((screen_point.y - (i as f64 + 0.5)).abs() < 1e-9);
+
+        let world_point = grid
+            .screen_to_world_transform()
+            .transform_point([j as f64 + 0.5, i as f64 + 0.5].into());
+        assert!((world_point.x - x).abs() < tolerance);
+        assert!((world_point.y - y).abs() < tolerance);
+    }
+}
+
+#[rstest]
+#[case(Registration::PixelIsArea)]
+#[case(Registration::PixelIsPoint)]
+fn test_rasterize_matches_coordinates(#[case] registration: Registration) {
+    let bounds = Bounds::new(0., 0., 10., 10.).unwrap();
+    let resolution = Resolution::square(1.).unwrap();
+    let mut grid = Grid::empty_from_resolution(
+        &bounds,
+        &resolution,
+        ExtentPolicy::Exact,
+        registration,
+        f64::NAN,
+    )
+    .unwrap();
+    let test_polygon =
+        polygon![(x: 2.2, y: 3.2), (x: 6.7, y: 3.2), (x: 6.7, y: 5.7), (x: 2.2, y: 5.7)];
+
+    grid.rasterize_polygons(&[test_polygon], &[1.]).unwrap();
+
+    let (half_width, half_height) = (grid.cell_width() / 2., grid.cell_height() / 2.);
+    for ((i, j, _), value) in grid.data().indexed_iter() {
+        let (x, y) = (grid.x()[[i, j]], grid.y()[[i, j]]);
+        let center_inside = (2.2..6.7).contains(&x) && (3.2..5.7).contains(&y);
+        let cell_overlaps = (2.2 - half_width..6.7 + half_width).contains(&x)
+            && (3.2 - half_height..5.7 + half_height).contains(&y);
+        if center_inside {
+            assert_eq!(*value, 1., "cell ({i}, {j}) at ({x}, {y})");
+        }
+        if !cell_overlaps {
+            assert!(value.is_nan(), "cell ({i}, {j}) at ({x}, {y})");
+        }
+    }
+}
+
 #[rstest]
 #[case(1, &*STUB_BOUNDS, 1, polygon![(x: 2., y: 2.), (x: 7., y: 2.), (x: 7., y: 7.), (x: 2., y: 7.), (x: 2., y: 2.)],  -3.0)]
 #[case(2, &*CONUS_BOUNDS, 2000, polygon![
@@ -100,6 +302,7 @@ fn test_rasterize_polygon(
     #[case] test_polygon: geo::Polygon<f64>,
     #[case] raster_label: f64,
 ) {
+    #[allow(deprecated)]
     let mut grid = Grid::empty_from_bounds(bounds, resolution, f64::NAN).unwrap();
 
     grid.rasterize_polygons(&[test_polygon], &[raster_label])
@@ -112,6 +315,7 @@ fn test_rasterize_polygon(
 #[case(&*STUB_BOUNDS, 1)]
 #[case(&*CONUS_BOUNDS, 8000)]
 fn test_iter_word_mut(#[case] bounds: &Bounds, #[case] resolution: usize) {
+    #[allow(deprecated)]
     let mut grid = Grid::empty_from_bounds(bounds, resolution, f64::NAN).unwrap();
     let x = grid.x().to_owned();
     let y = grid.y().to_owned();
@@ -130,3 +334,70 @@ fn test_iter_word_mut(#[case] bounds: &Bounds, #[case] resolution: usize) {
         assert_eq!(*data, expected_data);
     }
 }
+
+#[rstest]
+#[case(&*STUB_BOUNDS, 1.)]
+#[case(&*CONUS_BOUNDS, 8000.)]
+fn test_coordinates_are_broadcast(#[case] bounds: &Bounds, #[case] resolution: f64) {
+    let grid = build_empty_grid(bounds, resolution, f64::NAN);
+
+    assert_eq!(grid.x_coords().len(), grid.width());
+    assert_eq!(grid.y_coords().len(), grid.height());
+    assert_eq!(grid.x().shape(), &[grid.height(), grid.width()]);
+    assert_eq!(grid.y().shape(), &[grid.height(), grid.width()]);
+    // Broadcast views repeat one row or column without storing it again.
+    assert_eq!(grid.x().strides()[0], 0);
+    assert_eq!(grid.y().strides()[1], 0);
+
+    for ((i, j), &x) in grid.x().indexed_iter() {
+        assert_eq!(x, grid.x_coords()[j]);
+        assert_eq!(grid.y()[[i, j]], grid.y_coords()[i]);
+    }
+}
+
+fn rasterize_stub_polygon<T: CellValue>(label: T, nodata: T) -> Grid<T> {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., nodata);
+    let test_polygon =
+        polygon![(x: 2., y: 2.), (x: 7., y: 2.), (x: 7., y: 7.), (x: 2., y: 7.), (x: 2., y: 2.)];
+    grid.rasterize_polygons(&[test_polygon], &[label]).unwrap();
+    grid
+}
+
+#[rstest]
+#[case::f32(rasterize_stub_polygon(3f32, f32::NAN).cast(f64::NAN))]
+#[case::i32(rasterize_stub_polygon(3i32, -9999).cast(f64::NAN))]
+#[case::u8(rasterize_stub_polygon(3u8, 0).cast(f64::NAN))]
+fn test_rasterize_cell_types(#[case] grid: Grid) {
+    let expected = rasterize_stub_polygon(3., f64::NAN);
+
+    assert!(grid
+        .data()
+        .iter()
+        .zip(expected.data())
+        .all(|(value, expected)| equivalent(value, expected)));
+    utils::assert_grid_matches_snapshot(&grid, "test_rasterize_polygon_1");
+}
+
+#[rstest]
+fn test_cast() {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 10., f64::NAN);
+    grid.data_mut()
+        .indexed_iter_mut()
+        .skip(1)
+        .for_each(|((y, x, _), value)| *value = (y * 100 + x * 10) as f64 - 0.4);
+
+    let mask: Grid<u8> = grid.cast(u8::MAX);
+    assert_eq!(mask.nodata(), u8::MAX);
+    assert_eq!(mask.data()[[0, 0, 0]], u8::MAX);
+    assert_eq!(mask.data()[[0, 1, 0]], 10);
+    assert_eq!(mask.data()[[2, 5, 0]], 250);
+    assert_eq!(mask.x(), grid.x());
+    assert_eq!(mask.y(), grid.y());
+    assert_eq!(
+        mask.world_to_screen_transform(),
+        grid.world_to_screen_transform()
+    );
+
+    let values: Grid<i32> = grid.cast(i32::MIN);
+    assert_eq!(values.data()[[9, 9, 0]], 990);
+}
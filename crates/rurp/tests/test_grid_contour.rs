@@ -0,0 +1,286 @@
+use geo::{Area, Coord, Geometry, LineString, MultiPolygon};
+use rstest::rstest;
+use rurp::bounds::Bounds;
+use rurp::grid::contour::IsolineOptions;
+use rurp::grid::rasterize::RasterizeOptions;
+use rurp::grid::Grid;
+use rurp::interpolate::inverse_distance_weighting::interpolate;
+pub mod utils;
+use utils::{build_empty_grid, build_stub_points, build_world_grid, STUB_BOUNDS};
+
+/// Build a 10 x 10 grid of unit cells filled by a function of the cell center.
+fn build_grid(value: impl Fn(f64, f64) -> f64) -> Grid {
+    let bounds = Bounds::new(0., 0., 10., 10.).unwrap();
+    build_world_grid(&bounds, 1., value)
+}
+
+fn is_closed(line: &LineString<f64>) -> bool {
+    line.0.first() == line.0.last()
+}
+
+#[rstest]
+fn test_isoline_of_plane() {
+    let grid = build_grid(|x, _| x);
+
+    let isolines = grid.isolines(&[5.], IsolineOptions::default()).unwrap();
+
+    assert_eq!(isolines.len(), 1);
+    assert_eq!(isolines[0].0.len(), 1);
+    let line = &isolines[0].0[0];
+    assert_eq!(line.0.len(), 10);
+    assert!(line.0.iter().all(|coord| coord.x == 5.));
+    let mut ys: Vec<f64> = line.0.iter().map(|coord| coord.y).collect();
+    ys.sort_by(f64::total_cmp);
+    assert_eq!((ys[0], ys[9]), (0.5, 9.5));
+}
+
+#[rstest]
+fn test_isolines_of_cone() {
+    let distance = |x: f64, y: f64| ((x - 5.).powi(2) + (y - 5.).powi(2)).sqrt();
+    let grid = build_grid(distance);
+
+    let isolines = grid
+        .isolines(&[2., 3., 20.], IsolineOptions::default())
+        .unwrap();
+
+    assert_eq!(isolines.len(), 3);
+    for (isoline, level) in isolines[..2].iter().zip([2., 3.]) {
+        assert_eq!(isoline.0.len(), 1);
+        let ring = &isoline.0[0];
+        assert!(is_closed(ring));
+        assert!(ring
+            .0
+            .iter()
+            .all(|coord| (distance(coord.x, coord.y) - level).abs() < 0.1));
+    }
+    assert!(isolines[2].0.is_empty());
+}
+
+#[rstest]
+fn test_isolines_end_at_nodata() {
+    let mut grid = build_grid(|x, _| x);
+    grid.data_mut()[[5, 4, 0]] = f64::NAN;
+
+    let isolines = grid.isolines(&[5.], IsolineOptions::default()).unwrap();
+
+    let lines = &isolines[0].0;
+    assert_eq!(lines.len(), 2);
+    assert!(lines.iter().all(|line| !is_closed(line)));
+    // The crossings of the rows above and below the nodata cell stay, the crossing of its row does not.
+    let lengths: Vec<usize> = lines.iter().map(|line| line.0.len()).collect();
+    assert_eq!(lengths.iter().sum::<usize>(), 10 - 1);
+}
+
+#[rstest]
+#[case(5., 1)]
+#[case(5.5, 2)]
+fn test_saddle(#[case] level: f64, #[case] expected_count: usize) {
+    let mut grid = build_grid(|_, _| 0.);
+    // Two high cells on one diagonal, whose mean with the two low cells on the other is 5.
+    grid.data_mut()[[4, 4, 0]] = 10.;
+    grid.data_mut()[[5, 5, 0]] = 10.;
+
+    let isolines = grid.isolines(&[level], IsolineOptions::default()).unwrap();
+
+    assert_eq!(isolines[0].0.len(), expected_count);
+    assert!(isolines[0].0.iter().all(is_closed));
+}
+
+#[rstest]
+fn test_smoothing() {
+    let distance = |x: f64, y: f64| ((x - 5.).powi(2) + (y - 5.).powi(2)).sqrt();
+    let cone = build_grid(distance);
+    let plane = build_grid(|x, y| x + y);
+
+    let traced = cone.isolines(&[3.], IsolineOptions::default()).unwrap();
+    let smoothed = cone
+        .isolines(
+            &[3.],
+            IsolineOptions {
+                smoothing: 2,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let line = plane
+        .isolines(
+            &[9.],
+            IsolineOptions {
+                smoothing: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    let (traced, smoothed) = (&traced[0].0[0], &smoothed[0].0[0]);
+    assert!(is_closed(smoothed));
+    assert_eq!(smoothed.0.len() - 1, (traced.0.len() - 1) * 4);
+    let line = &line[0].0[0];
+    assert!(!is_closed(line));
+    let ends: Vec<&Coord<f64>> = vec![line.0.first().unwrap(), line.0.last().unwrap()];
+    assert!(ends.iter().all(|coord| coord.x + coord.y == 9.));
+}
+
+#[rstest]
+#[case(IsolineOptions { band: 1, ..Default::default() }, &[1.], "Band is out of range")]
+#[case(IsolineOptions::default(), &[1., f64::NAN], "Levels must be finite")]
+fn test_isoline_errors(
+    #[case] options: IsolineOptions,
+    #[case] levels: &[f64],
+    #[case] expected: &str,
+) {
+    let grid = build_grid(|x, _| x);
+
+    let result = grid.isolines(levels, options);
+
+    assert_eq!(result.unwrap_err().to_string(), expected);
+}
+
+#[rstest]
+fn test_isolines_of_interpolation() {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    let points = build_stub_points(&STUB_BOUNDS, &50);
+    interpolate(&mut grid, &points, 2., 30., 0);
+
+    let levels: Vec<f64> = (1..12).map(|level| f64::from(level) * 10.).collect();
+    let isolines = grid
+        .isolines(
+            &levels,
+            IsolineOptions {
+                smoothing: 2,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    let geometries: Vec<Geometry<f64>> = isolines.into_iter().map(Into::into).collect();
+    let mut lines = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    lines.data_mut().fill(0.);
+    lines
+        .rasterize_geometries(&geometries, &levels, RasterizeOptions::default())
+        .unwrap();
+
+    utils::assert_grid_matches_snapshot(&lines, "test_isolines_1");
+}
+
+/// Assert that every polygon has a counterclockwise exterior and clockwise holes.
+fn assert_oriented(multi_polygon: &MultiPolygon<f64>) {
+    let signed_area =
+        |ring: &LineString<f64>| geo::Polygon::new(ring.clone(), vec![]).signed_area();
+    for polygon in multi_polygon {
+        assert!(is_closed(polygon.exterior()));
+        assert!(signed_area(polygon.exterior()) > 0.);
+        assert!(polygon
+            .interiors()
+            .iter()
+            .all(|interior| is_closed(interior) && signed_area(interior) < 0.));
+    }
+}
+
+#[rstest]
+fn test_isobands_of_plane() {
+    let grid = build_grid(|x, _| x);
+
+    let isobands = grid.isobands(&[2., 5., 8.], 0).unwrap();
+
+    assert_eq!(isobands.len(), 2);
+    for isoband in &isobands {
+        assert_oriented(isoband);
+        assert_eq!(isoband.0.len(), 1);
+        // Cell centers span 0.5 to 9.5 vertically, and each isoband is 3 wide.
+        assert!((isoband.unsigned_area() - 27.).abs() < 1e-9);
+    }
+    let xs: Vec<f64> = isobands[0].0[0]
+        .exterior()
+        .coords()
+        .map(|coord| coord.x)
+        .collect();
+    assert!(xs.iter().all(|&x| (2. ..=5.).contains(&x)));
+}
+
+#[rstest]
+fn test_isoband_with_hole() {
+    let distance = |x: f64, y: f64| ((x - 5.).powi(2) + (y - 5.).powi(2)).sqrt();
+    let grid = build_grid(distance);
+
+    let isobands = grid.isobands(&[1., 2., 3.], 0).unwrap();
+
+    for isoband in &isobands {
+        assert_oriented(isoband);
+        assert_eq!(isoband.0.len(), 1);
+        assert_eq!(isoband.0[0].interiors().len(), 1);
+    }
+}
+
+#[rstest]
+fn test_isobands_tile_seamlessly() {
+    let distance = |x: f64, y: f64| ((x - 3.).powi(2) + (y - 6.).powi(2)).sqrt();
+    let mut grid = build_grid(|x, y| distance(x, y) + (x * 2.).sin());
+    grid.data_mut()[[2, 7, 0]] = f64::NAN;
+
+    let breaks = [f64::NEG_INFINITY, 1., 2.5, 4., 5.5, f64::INFINITY];
+    let isobands = grid.isobands(&breaks, 0).unwrap();
+
+    isobands.iter().for_each(assert_oriented);
+    let total_area: f64 = isobands.iter().map(Area::unsigned_area).sum();
+    // Cell centers span 9 x 9, less the four squares around the nodata cell.
+    assert!((total_area - (81. - 4.)).abs() < 1e-9, "{total_area}");
+}
+
+#[rstest]
+#[case(&[1., 2.], 1, "Band is out of range")]
+#[case(&[1.], 0, "At least two breaks are required")]
+#[case(&[1., 3., 3.], 0, "Breaks must be strictly increasing")]
+#[case(&[1., f64::NAN], 0, "Breaks must be strictly increasing")]
+fn test_isoband_errors(#[case] breaks: &[f64], #[case] band: usize, #[case] expected: &str) {
+    let grid = build_grid(|x, _| x);
+
+    let result = grid.isobands(breaks, band);
+
+    assert_eq!(result.unwrap_err().to_string(), expected);
+}
+
+#[rstest]
+fn test_isobands_of_interpolation() {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    let points = build_stub_points(&STUB_BOUNDS, &50);
+    interpolate(&mut grid, &points, 2., 30., 0);
+
+    let breaks: Vec<f64> = (0..13).map(|level| f64::from(level) * 10.).collect();
+    let isobands = grid.isobands(&breaks, 0).unwrap();
+
+    let geometries: Vec<Geometry<f64>> = isobands.into_iter().map(Into::into).collect();
+    let mut bands = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    bands
+        .rasterize_geometries(&geometries, &breaks[..12], RasterizeOptions::default())
+        .unwrap();
+
+    utils::assert_grid_matches_snapshot(&bands, "test_isobands_1");
+}
+
+#[rstest]
+fn test_isoband_rings_touching_at_a_node() {
+    // The cell at the exclusive upper break touches the band only at its center,
+    // where the band's boundary meets itself.
+    let bounds = Bounds::new(0., 0., 3., 3.).unwrap();
+    let mut grid = build_empty_grid(&bounds, 1., f64::NAN);
+    grid.data_mut().fill(-100.);
+    grid.data_mut()[[0, 1, 0]] = 1.5;
+    grid.data_mut()[[1, 1, 0]] = 100.;
+
+    let isobands = grid.isobands(&[0.5, 1.5], 0).unwrap();
+
+    let isoband = &isobands[0];
+    assert_oriented(isoband);
+    assert_eq!(isoband.0.len(), 1);
+    assert_eq!(isoband.0[0].interiors().len(), 1);
+    let node = Coord { x: 1.5, y: 0.5 };
+    for ring in std::iter::once(isoband.0[0].exterior()).chain(isoband.0[0].interiors()) {
+        // Each ring passes the node once, so the hole touches the exterior there without crossing it.
+        let nodes = &ring.0[..ring.0.len() - 1];
+        assert_eq!(nodes.iter().filter(|coord| **coord == node).count(), 1);
+        for (idx, coord) in nodes.iter().enumerate() {
+            assert!(!nodes[idx + 1..].contains(coord), "{ring:?}");
+        }
+    }
+}
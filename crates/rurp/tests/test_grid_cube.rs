@@ -0,0 +1,319 @@
+use rstest::rstest;
+use rurp::grid::cube::{GridCube, TemporalAggregation, TemporalInterpolation, SECONDS_PER_DAY};
+use rurp::grid::Grid;
+use rurp::Timestamp;
+pub mod utils;
+use utils::{build_empty_grid, STUB_BOUNDS};
+
+const HOUR: Timestamp = 3600;
+/// 2024-01-01T00:00:00Z
+const JANUARY_FIRST: Timestamp = 1_704_067_200;
+
+/// Build hourly grids over two days, where each cell holds the hour since the start
+/// plus its column, and the first column is nodata at odd hours.
+fn build_hourly_grids() -> Vec<(Timestamp, Grid)> {
+    (0..48)
+        .map(|hour| {
+            let mut grid = build_empty_grid(&STUB_BOUNDS, 25., f64::NAN);
+            grid.data_mut()
+                .indexed_iter_mut()
+                .for_each(|((_, column, _), value)| {
+                    if column > 0 || hour % 2 == 0 {
+                        *value = (hour + column) as f64;
+                    }
+                });
+            (JANUARY_FIRST + hour as Timestamp * HOUR, grid)
+        })
+        .collect()
+}
+
+#[rstest]
+fn test_from_grids() {
+    let mut grids = build_hourly_grids();
+    grids.reverse();
+
+    let cube = GridCube::from_grids(grids).unwrap();
+
+    assert_eq!(cube.steps(), 48);
+    assert_eq!(cube.data().shape(), &[48, 4, 4, 1]);
+    assert_eq!(cube.timestamps()[0], JANUARY_FIRST);
+    assert_eq!(cube.timestamps()[47], JANUARY_FIRST + 47 * HOUR);
+    assert_eq!(cube.step(5).data()[[0, 2, 0]], 7.);
+    assert_eq!(
+        cube.at(JANUARY_FIRST + 10 * HOUR).unwrap().data()[[3, 3, 0]],
+        13.
+    );
+    assert!(cube.at(JANUARY_FIRST + 30).is_none());
+    assert_eq!(cube.step(0).x(), cube.x());
+}
+
+#[rstest]
+#[case(JANUARY_FIRST, JANUARY_FIRST + SECONDS_PER_DAY, 24, JANUARY_FIRST)]
+#[case(JANUARY_FIRST + 90 * 60, JANUARY_FIRST + 4 * HOUR, 2, JANUARY_FIRST + 2 * HOUR)]
+#[case(JANUARY_FIRST + 46 * HOUR, JANUARY_FIRST + 100 * HOUR, 2, JANUARY_FIRST + 46 * HOUR)]
+fn test_slice_time(
+    #[case] start: Timestamp,
+    #[case] end: Timestamp,
+    #[case] expected_steps: usize,
+    #[case] expected_first: Timestamp,
+) {
+    let cube = GridCube::from_grids(build_hourly_grids()).unwrap();
+
+    let slice = cube.slice_time(start, end);
+
+    assert_eq!(slice.steps(), expected_steps);
+    assert_eq!(slice.data().shape()[0], expected_steps);
+    assert_eq!(slice.timestamps()[0], expected_first);
+    assert_eq!(
+        slice.step(0).data(),
+        cube.at(expected_first).unwrap().data()
+    );
+}
+
+#[rstest]
+fn test_slice_time_empty() {
+    let cube = GridCube::from_grids(build_hourly_grids()).unwrap();
+
+    let slice = cube.slice_time(JANUARY_FIRST - SECONDS_PER_DAY, JANUARY_FIRST);
+
+    assert_eq!(slice.steps(), 0);
+    assert_eq!(slice.data().shape(), &[0, 4, 4, 1]);
+}
+
+#[rstest]
+#[case(TemporalAggregation::Mean, [11.5, 35.5], [11., 35.])]
+#[case(TemporalAggregation::Min, [0., 24.], [0., 24.])]
+#[case(TemporalAggregation::Max, [23., 47.], [22., 46.])]
+#[case(TemporalAggregation::Sum, [276., 852.], [132., 420.])]
+fn test_aggregate_daily(
+    #[case] aggregation: TemporalAggregation,
+    #[case] expected: [f64; 2],
+    #[case] expected_with_nodata: [f64; 2],
+) {
+    let cube = GridCube::from_grids(build_hourly_grids()).unwrap();
+
+    let daily = cube.aggregate_daily(aggregation).unwrap();
+
+    assert_eq!(
+        daily.timestamps(),
+        &[JANUARY_FIRST, JANUARY_FIRST + SECONDS_PER_DAY]
+    );
+    for (day, (expected, expected_with_nodata)) in
+        expected.iter().zip(expected_with_nodata).enumerate()
+    {
+        let offset = if aggregation == TemporalAggregation::Sum {
+            24.
+        } else {
+            1.
+        };
+        assert_eq!(daily.data()[[day, 2, 1, 0]], expected + offset);
+        assert_eq!(daily.data()[[day, 2, 0, 0]], expected_with_nodata);
+    }
+}
+
+#[rstest]
+fn test_aggregate_all_nodata() {
+    let mut grids = build_hourly_grids();
+    grids.truncate(24);
+    grids
+        .iter_mut()
+        .for_each(|(_, grid)| grid.data_mut()[[1, 1, 0]] = f64::NAN);
+    let cube = GridCube::from_grids(grids).unwrap();
+
+    let total = cube.aggregate(TemporalAggregation::Sum);
+
+    assert!(total.data()[[1, 1, 0]].is_nan());
+    assert_eq!(total.data()[[1, 2, 0]], 276. + 48.);
+}
+
+#[rstest]
+fn test_aggregate_by_period() {
+    let cube = GridCube::from_grids(build_hourly_grids()).unwrap();
+
+    let six_hourly = cube
+        .aggregate_by_period(6 * HOUR, TemporalAggregation::Max)
+        .unwrap();
+
+    assert_eq!(six_hourly.steps(), 8);
+    assert_eq!(six_hourly.timestamps()[1], JANUARY_FIRST + 6 * HOUR);
+    assert_eq!(six_hourly.data()[[1, 0, 3, 0]], 14.);
+}
+
+#[rstest]
+fn test_empty_from_grid() {
+    let grid: Grid<f32> = build_empty_grid(&STUB_BOUNDS, 10., f32::NAN);
+    let timestamps = (0..24).map(|hour| JANUARY_FIRST + hour * HOUR).collect();
+
+    let mut cube = GridCube::empty_from_grid(&grid, timestamps, 2).unwrap();
+    cube.data_mut().fill(1.);
+
+    assert_eq!(cube.data().shape(), &[24, 10, 10, 2]);
+    let daily = cube.aggregate_daily(TemporalAggregation::Sum).unwrap();
+    assert_eq!(daily.steps(), 1);
+    assert!(daily.data().iter().all(|&value| value == 24.));
+}
+
+#[rstest]
+fn test_from_grids_errors() {
+    assert_eq!(
+        GridCube::<f64>::from_grids(vec![]).unwrap_err().to_string(),
+        "At least one grid is required"
+    );
+
+    let mut grids = build_hourly_grids();
+    grids[1].0 = grids[0].0;
+    assert_eq!(
+        GridCube::from_grids(grids).unwrap_err().to_string(),
+        "Timestamps must be strictly increasing"
+    );
+
+    let mut grids = build_hourly_grids();
+    grids[3].1 = build_empty_grid(&STUB_BOUNDS, 10., f64::NAN);
+    assert_eq!(
+        GridCube::from_grids(grids).unwrap_err().to_string(),
+        "Grids must share one geometry"
+    );
+
+    let mut grids = build_hourly_grids();
+    grids[3].1.reset_bands(2);
+    assert_eq!(
+        GridCube::from_grids(grids).unwrap_err().to_string(),
+        "Grids must have the same number of bands"
+    );
+
+    let cube = GridCube::from_grids(build_hourly_grids()).unwrap();
+    assert_eq!(
+        cube.aggregate_by_period(0, TemporalAggregation::Mean)
+            .unwrap_err()
+            .to_string(),
+        "Period must be positive"
+    );
+}
+
+/// Build six-hourly grids where cell (row, column) holds `f(hours)` plus the column,
+/// with cell (0, 0) nodata at 06Z.
+fn build_six_hourly_cube(f: impl Fn(f64) -> f64) -> GridCube {
+    let grids = (0..5)
+        .map(|step| {
+            let hours = (step * 6) as f64;
+            let mut grid = build_empty_grid(&STUB_BOUNDS, 50., f64::NAN);
+            grid.data_mut()
+                .indexed_iter_mut()
+                .for_each(|((row, column, _), value)| {
+                    if row > 0 || column > 0 || step != 1 {
+                        *value = f(hours) + column as f64;
+                    }
+                });
+            (JANUARY_FIRST + step * 6 * HOUR, grid)
+        })
+        .collect();
+    GridCube::from_grids(grids).unwrap()
+}
+
+#[rstest]
+#[case(TemporalInterpolation::Linear, 3, 1.5)]
+#[case(TemporalInterpolation::Linear, 8, 4.)]
+#[case(TemporalInterpolation::Linear, 24, 12.)]
+#[case(TemporalInterpolation::Cubic, 3, 1.5)]
+#[case(TemporalInterpolation::Cubic, 13, 6.5)]
+fn test_interpolate_time_linear_trend(
+    #[case] method: TemporalInterpolation,
+    #[case] hours: Timestamp,
+    #[case] expected: f64,
+) {
+    let cube = build_six_hourly_cube(|hours| hours / 2.);
+
+    let grid = cube
+        .interpolate_time(JANUARY_FIRST + hours * HOUR, method)
+        .unwrap();
+
+    assert!((grid.data()[[1, 1, 0]] - (expected + 1.)).abs() < 1e-9);
+    assert_eq!(grid.x(), cube.x());
+}
+
+#[rstest]
+#[case(9)]
+#[case(15)]
+#[case(17)]
+fn test_interpolate_time_cubic_reproduces_quadratic(#[case] hours: Timestamp) {
+    let quadratic = |hours: f64| 0.1 * hours * hours - hours + 3.;
+    let cube = build_six_hourly_cube(quadratic);
+
+    let cubic = cube
+        .interpolate_time(JANUARY_FIRST + hours * HOUR, TemporalInterpolation::Cubic)
+        .unwrap();
+    let linear = cube
+        .interpolate_time(JANUARY_FIRST + hours * HOUR, TemporalInterpolation::Linear)
+        .unwrap();
+
+    let expected = quadratic(hours as f64);
+    assert!((cubic.data()[[1, 0, 0]] - expected).abs() < 1e-9);
+    assert!((linear.data()[[1, 0, 0]] - expected).abs() > 1e-3);
+}
+
+#[rstest]
+#[case(TemporalInterpolation::Linear, 3, true)]
+#[case(TemporalInterpolation::Linear, 9, true)]
+#[case(TemporalInterpolation::Linear, 15, false)]
+#[case(TemporalInterpolation::Cubic, 9, true)]
+#[case(TemporalInterpolation::Cubic, 15, false)]
+#[case(TemporalInterpolation::Cubic, 6, true)]
+fn test_interpolate_time_nodata(
+    #[case] method: TemporalInterpolation,
+    #[case] hours: Timestamp,
+    #[case] expected_nodata: bool,
+) {
+    let cube = build_six_hourly_cube(|hours| hours);
+
+    let grid = cube
+        .interpolate_time(JANUARY_FIRST + hours * HOUR, method)
+        .unwrap();
+
+    assert_eq!(grid.data()[[0, 0, 0]].is_nan(), expected_nodata);
+    assert!(!grid.data()[[0, 1, 0]].is_nan());
+}
+
+#[rstest]
+fn test_interpolate_time_matching_step() {
+    let cube = build_six_hourly_cube(|hours| hours * hours);
+
+    let grid = cube
+        .interpolate_time(JANUARY_FIRST + 12 * HOUR, TemporalInterpolation::Cubic)
+        .unwrap();
+
+    assert_eq!(grid.data(), cube.step(2).data());
+}
+
+#[rstest]
+#[case(-1)]
+#[case(24 * HOUR + 1)]
+fn test_interpolate_time_out_of_range(#[case] offset: Timestamp) {
+    let cube = build_six_hourly_cube(|hours| hours);
+
+    let result = cube.interpolate_time(JANUARY_FIRST + offset, TemporalInterpolation::Linear);
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Timestamp is outside of the cube"
+    );
+}
+
+#[rstest]
+fn test_interpolate_times() {
+    let cube = build_six_hourly_cube(|hours| hours);
+    let timestamps: Vec<Timestamp> = (0..=24).map(|hours| JANUARY_FIRST + hours * HOUR).collect();
+
+    let hourly = cube
+        .interpolate_times(&timestamps, TemporalInterpolation::Linear)
+        .unwrap();
+
+    assert_eq!(hourly.steps(), 25);
+    hourly
+        .timestamps()
+        .iter()
+        .enumerate()
+        .for_each(|(hours, &timestamp)| {
+            assert_eq!(timestamp, timestamps[hours]);
+            assert!((hourly.data()[[hours, 1, 1, 0]] - (hours as f64 + 1.)).abs() < 1e-9);
+        });
+}
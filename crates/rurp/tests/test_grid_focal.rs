@@ -0,0 +1,151 @@
+use ndarray::prelude::*;
+use rstest::rstest;
+use rurp::bounds::Bounds;
+use rurp::grid::focal::FocalOperation;
+use rurp::grid::Grid;
+use rurp::interpolate::inverse_distance_weighting::interpolate;
+pub mod utils;
+use utils::{build_empty_grid, build_indexed_grid, build_stub_points, STUB_BOUNDS};
+
+/// Build a 5 x 5 grid of unit cells where each cell holds its row times 5 plus its column.
+fn build_counting_grid() -> Grid {
+    let bounds = Bounds::new(0., 0., 5., 5.).unwrap();
+    build_indexed_grid(&bounds, 1., |row, column| (row * 5 + column) as f64)
+}
+
+#[rstest]
+#[case(FocalOperation::Mean, 12., 3.)]
+#[case(
+    FocalOperation::Gaussian { sigma: 1. },
+    12.,
+    (6. * (-0.5_f64).exp() + 6. * (-1_f64).exp()) / (1. + 2. * (-0.5_f64).exp() + (-1_f64).exp())
+)]
+#[case(FocalOperation::Median, 12., 3.)]
+#[case(FocalOperation::Min, 6., 0.)]
+#[case(FocalOperation::Max, 18., 6.)]
+fn test_focal_operations(
+    #[case] operation: FocalOperation,
+    #[case] expected_center: f64,
+    #[case] expected_corner: f64,
+) {
+    let grid = build_counting_grid();
+
+    let filtered = grid.focal(operation, 1).unwrap();
+
+    assert!((filtered.data()[[2, 2, 0]] - expected_center).abs() < 1e-9);
+    // The window of the bottom left corner holds 0, 1, 5 and 6.
+    assert!((filtered.data()[[0, 0, 0]] - expected_corner).abs() < 1e-9);
+    assert_eq!(filtered.bounds(), grid.bounds());
+}
+
+#[rstest]
+fn test_focal_mean_renormalizes_around_nodata() {
+    let mut grid = build_counting_grid();
+    grid.data_mut()[[2, 3, 0]] = f64::NAN;
+
+    let filtered = grid.focal(FocalOperation::Mean, 1).unwrap();
+
+    assert!(filtered.data()[[2, 3, 0]].is_nan());
+    let neighbors = [6., 7., 8., 11., 12., 16., 17., 18.];
+    assert_eq!(
+        filtered.data()[[2, 2, 0]],
+        neighbors.iter().sum::<f64>() / 8.
+    );
+}
+
+#[rstest]
+fn test_convolve_custom_kernel() {
+    let grid = build_counting_grid();
+    // The difference between the cells above and below.
+    let kernel = array![[0., -0.5, 0.], [0., 0., 0.], [0., 0.5, 0.]];
+
+    let gradient = grid.convolve(&kernel).unwrap();
+
+    assert_eq!(gradient.data()[[2, 2, 0]], 5.);
+    // The weights sum to zero, so where the cell below is missing there is no result.
+    assert!(gradient.data()[[0, 2, 0]].is_nan());
+    assert!(gradient.data()[[4, 2, 0]].is_nan());
+    assert_eq!(gradient.data()[[1, 0, 0]], 5.);
+}
+
+#[rstest]
+fn test_convolve_renormalizes_signed_weights() {
+    let grid = build_counting_grid();
+    // A sharpening kernel along rows, whose weights sum to one.
+    let kernel = array![[-1., 3., -1.]];
+
+    let sharpened = grid.convolve(&kernel).unwrap();
+
+    assert_eq!(sharpened.data()[[2, 2, 0]], 12.);
+    // At the left edge the weights present sum to 2, so the sum is scaled by 1 / 2.
+    assert_eq!(sharpened.data()[[2, 0, 0]], (3. * 10. - 11.) / 2.);
+}
+
+#[rstest]
+fn test_convolve_zero_sum_kernel_next_to_nodata() {
+    let mut grid = build_counting_grid();
+    grid.data_mut()[[2, 3, 0]] = f64::NAN;
+    let laplacian = array![[0., 1., 0.], [1., -4., 1.], [0., 1., 0.]];
+
+    let filtered = grid.convolve(&laplacian).unwrap();
+
+    // The counting grid is linear, so its Laplacian is zero wherever the window is complete.
+    assert_eq!(filtered.data()[[1, 1, 0]], 0.);
+    assert!(filtered.data()[[2, 2, 0]].is_nan());
+    assert!(filtered.data()[[1, 3, 0]].is_nan());
+    // Missing cells with a zero weight do not matter.
+    assert_eq!(filtered.data()[[1, 2, 0]], 0.);
+}
+
+#[rstest]
+fn test_focal_filters_every_band() {
+    let mut grid = build_counting_grid();
+    grid.reset_bands(2);
+    grid.data_mut()
+        .indexed_iter_mut()
+        .for_each(|((row, column, band), value)| *value = ((row + column) * (band + 1)) as f64);
+
+    let filtered = grid.focal(FocalOperation::Max, 2).unwrap();
+
+    assert_eq!(filtered.data()[[2, 2, 0]], 8.);
+    assert_eq!(filtered.data()[[2, 2, 1]], 16.);
+    assert_eq!(filtered.data()[[0, 0, 1]], 8.);
+}
+
+#[rstest]
+#[case(array![[1., 1.], [1., 1.]], "Kernel must have odd dimensions")]
+#[case(array![[0., 0., 0.]], "Kernel weights must not all be zero")]
+fn test_convolve_errors(#[case] kernel: Array2<f64>, #[case] expected: &str) {
+    let grid = build_counting_grid();
+
+    let result = grid.convolve(&kernel);
+
+    assert_eq!(result.unwrap_err().to_string(), expected);
+}
+
+#[rstest]
+#[case(0.)]
+#[case(f64::NAN)]
+fn test_gaussian_sigma_error(#[case] sigma: f64) {
+    let grid = build_counting_grid();
+
+    let result = grid.focal(FocalOperation::Gaussian { sigma }, 1);
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Sigma must be positive and finite"
+    );
+}
+
+#[rstest]
+fn test_gaussian_smoothing_of_interpolation() {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    let points = build_stub_points(&STUB_BOUNDS, &50);
+    interpolate(&mut grid, &points, 2., 30., 0);
+
+    let smoothed = grid
+        .focal(FocalOperation::Gaussian { sigma: 2. }, 6)
+        .unwrap();
+
+    utils::assert_grid_matches_snapshot(&smoothed, "test_focal_gaussian_1");
+}
@@ -0,0 +1,131 @@
+use geo::{coord, polygon, MultiPolygon};
+use rstest::rstest;
+use rurp::bounds::Bounds;
+use rurp::grid::mask::MaskRegion;
+use rurp::grid::Grid;
+use rurp::interpolate::inverse_distance_weighting::interpolate;
+pub mod utils;
+use utils::{build_empty_grid, build_stub_points, CONUS_BOUNDS, STUB_BOUNDS};
+
+/// Build a grid with two bands where every cell holds one in the first band and two in the second.
+fn build_filled_grid() -> Grid {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    grid.reset_bands(2);
+    grid.data_mut()
+        .indexed_iter_mut()
+        .for_each(|((_, _, band), value)| *value = (band + 1) as f64);
+    grid
+}
+
+#[rstest]
+#[case(MaskRegion::Outside, 1., f64::NAN)]
+#[case(MaskRegion::Inside, f64::NAN, 1.)]
+fn test_mask_polygons(
+    #[case] region: MaskRegion,
+    #[case] expected_inside: f64,
+    #[case] expected_outside: f64,
+) {
+    let mut grid = build_filled_grid();
+    let square = polygon![(x: 20., y: 20.), (x: 60., y: 20.), (x: 60., y: 60.), (x: 20., y: 60.)];
+
+    grid.mask_polygons(&[square], region).unwrap();
+
+    for (row, column) in [(30, 30), (21, 58), (58, 21)] {
+        let inside = grid.data()[[row, column, 0]];
+        assert!(inside == expected_inside || inside.is_nan() && expected_inside.is_nan());
+        assert_eq!(
+            grid.data()[[row, column, 1]].is_nan(),
+            expected_inside.is_nan()
+        );
+    }
+    for (row, column) in [(10, 10), (30, 70), (70, 30), (99, 99)] {
+        let outside = grid.data()[[row, column, 0]];
+        assert!(outside == expected_outside || outside.is_nan() && expected_outside.is_nan());
+        assert_eq!(
+            grid.data()[[row, column, 1]].is_nan(),
+            expected_outside.is_nan()
+        );
+    }
+}
+
+#[rstest]
+fn test_mask_multi_polygons() {
+    let mut grid = build_filled_grid();
+    let square_with_hole = polygon!(
+        exterior: [(x: 10., y: 10.), (x: 50., y: 10.), (x: 50., y: 50.), (x: 10., y: 50.)],
+        interiors: [[(x: 20., y: 20.), (x: 40., y: 20.), (x: 40., y: 40.), (x: 20., y: 40.)]],
+    );
+    let small_square =
+        polygon![(x: 70., y: 70.), (x: 90., y: 70.), (x: 90., y: 90.), (x: 70., y: 90.)];
+    let multi_polygon = MultiPolygon::new(vec![square_with_hole, small_square]);
+
+    grid.mask_multi_polygons(&[multi_polygon], MaskRegion::Outside)
+        .unwrap();
+
+    assert_eq!(grid.data()[[15, 15, 0]], 1.);
+    assert_eq!(grid.data()[[80, 80, 1]], 2.);
+    assert!(grid.data()[[30, 30, 0]].is_nan());
+    assert!(grid.data()[[60, 60, 1]].is_nan());
+}
+
+#[rstest]
+fn test_mask_interpolation_to_polygon() {
+    let bounds = &*CONUS_BOUNDS;
+    let mut grid = build_empty_grid(bounds, 16000., f64::NAN);
+    let points = build_stub_points(bounds, &10000);
+    interpolate(&mut grid, &points, 2., 250_000., 0);
+    let region = polygon![
+        coord! {x: -1_951_222.716_269_676_6, y: 2_354_912.258_633_185},
+        coord! {x: -2_041_264.291_279_755_5, y: 2_023_308.620_816_362_3},
+        coord! {x: -1_678_141.122_611_409_3, y: 1_486_297.116_022_01},
+        coord! {x: -1_665_558.550_028_715, y: 1_608_253.256_388_631_6},
+        coord! {x: -1_601_328.924_522_811_5, y: 1_609_401.564_378_187_3},
+        coord! {x: -1_476_039.396_968_355_9, y: 2_250_299.151_204_386_3},
+        coord! {x: -1_951_222.716_269_676_6, y: 2_354_912.258_633_185},
+    ];
+
+    grid.mask_polygons(&[region], MaskRegion::Outside).unwrap();
+
+    utils::assert_grid_matches_snapshot(&grid, "test_mask_polygons_1");
+}
+
+#[rstest]
+#[case(MaskRegion::Outside, [1., f64::NAN, f64::NAN])]
+#[case(MaskRegion::Inside, [f64::NAN, 1., 1.])]
+fn test_mask_by_grid(#[case] region: MaskRegion, #[case] expected: [f64; 3]) {
+    let mut grid = build_filled_grid();
+    // Land is one, sea is zero, and unknown is nodata.
+    let mut land_sea: Grid<u8> = build_empty_grid(&STUB_BOUNDS, 1., u8::MAX);
+    land_sea
+        .data_mut()
+        .indexed_iter_mut()
+        .for_each(|((_, column, _), value)| {
+            *value = match column {
+                0..=49 => 1,
+                50..=89 => 0,
+                _ => u8::MAX,
+            }
+        });
+
+    grid.mask_by_grid(&land_sea, region).unwrap();
+
+    for (column, expected) in [10, 60, 95].into_iter().zip(expected) {
+        let value = grid.data()[[40, column, 0]];
+        assert!(value == expected || value.is_nan() && expected.is_nan());
+        assert_eq!(grid.data()[[40, column, 1]].is_nan(), expected.is_nan());
+    }
+}
+
+#[rstest]
+fn test_mask_by_grid_geometry_mismatch() {
+    let mut grid = build_filled_grid();
+    let bounds = Bounds::new(0., 0., 100., 50.).unwrap();
+    let mask = build_empty_grid(&bounds, 1., f64::NAN);
+
+    let result = grid.mask_by_grid(&mask, MaskRegion::Outside);
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Mask must have the same geometry as the grid"
+    );
+}
@@ -0,0 +1,123 @@
+use geo::{Area, BoundingRect, Geometry, LineString};
+use rstest::rstest;
+use rurp::bounds::Bounds;
+use rurp::grid::polygonize::Connectivity;
+use rurp::grid::rasterize::RasterizeOptions;
+use rurp::grid::Grid;
+use rurp::interpolate::inverse_distance_weighting::interpolate;
+pub mod utils;
+use utils::{build_empty_grid, build_indexed_grid, build_stub_points, STUB_BOUNDS};
+
+/// Build a 10 x 10 grid of unit cells filled with zero.
+fn build_zero_grid() -> Grid {
+    let bounds = Bounds::new(0., 0., 10., 10.).unwrap();
+    build_indexed_grid(&bounds, 1., |_, _| 0.)
+}
+
+fn signed_area(ring: &LineString<f64>) -> f64 {
+    geo::Polygon::new(ring.clone(), vec![]).signed_area()
+}
+
+#[rstest]
+fn test_polygonize_block() {
+    let mut grid = build_zero_grid();
+    grid.data_mut()
+        .indexed_iter_mut()
+        .filter(|((row, column, _), _)| (2..5).contains(row) && (3..7).contains(column))
+        .for_each(|(_, value)| *value = 1.);
+
+    let regions = grid.polygonize(0, Connectivity::Four).unwrap();
+
+    assert_eq!(regions.len(), 2);
+    let (background_value, background) = &regions[0];
+    let (block_value, block) = &regions[1];
+    assert_eq!((*background_value, *block_value), (0., 1.));
+    assert_eq!(background.0.len(), 1);
+    assert_eq!(background.0[0].interiors().len(), 1);
+    assert_eq!(background.unsigned_area(), 100. - 12.);
+    assert!(signed_area(background.0[0].exterior()) > 0.);
+    assert!(signed_area(&background.0[0].interiors()[0]) < 0.);
+    assert_eq!(block.unsigned_area(), 12.);
+    let rect = block.bounding_rect().unwrap();
+    assert_eq!((rect.min().x, rect.min().y), (3., 2.));
+    assert_eq!((rect.max().x, rect.max().y), (7., 5.));
+}
+
+#[rstest]
+#[case(Connectivity::Four, vec![(0., 1, 2), (1., 1, 0), (1., 1, 0)])]
+#[case(Connectivity::Eight, vec![(0., 1, 2), (1., 2, 0)])]
+fn test_diagonal_cells(
+    #[case] connectivity: Connectivity,
+    #[case] expected: Vec<(f64, usize, usize)>,
+) {
+    let mut grid = build_zero_grid();
+    grid.data_mut()[[2, 2, 0]] = 1.;
+    grid.data_mut()[[3, 3, 0]] = 1.;
+
+    let regions = grid.polygonize(0, connectivity).unwrap();
+
+    // The value, polygon count and hole count of each region.
+    let summary: Vec<(f64, usize, usize)> = regions
+        .iter()
+        .map(|(value, polygons)| {
+            let holes = polygons
+                .iter()
+                .map(|polygon| polygon.interiors().len())
+                .sum();
+            (*value, polygons.0.len(), holes)
+        })
+        .collect();
+    assert_eq!(summary, expected);
+}
+
+#[rstest]
+fn test_nodata_is_not_polygonized() {
+    let mut grid = build_zero_grid();
+    grid.data_mut()
+        .indexed_iter_mut()
+        .filter(|((_, column, _), _)| *column >= 5)
+        .for_each(|(_, value)| *value = f64::NAN);
+
+    let regions = grid.polygonize(0, Connectivity::Eight).unwrap();
+
+    assert_eq!(regions.len(), 1);
+    assert_eq!(regions[0].1.unsigned_area(), 50.);
+}
+
+#[rstest]
+fn test_polygonize_band_out_of_range() {
+    let grid = build_zero_grid();
+
+    let result = grid.polygonize(1, Connectivity::Four);
+
+    assert_eq!(result.unwrap_err().to_string(), "Band is out of range");
+}
+
+#[rstest]
+#[case(Connectivity::Four)]
+#[case(Connectivity::Eight)]
+fn test_polygonize_round_trip(#[case] connectivity: Connectivity) {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    let points = build_stub_points(&STUB_BOUNDS, &50);
+    interpolate(&mut grid, &points, 2., 30., 0);
+    // Classify into categories of 25, leaving the cells without points in range as nodata.
+    grid.data_mut()
+        .iter_mut()
+        .for_each(|value| *value = (*value / 25.).floor());
+
+    let regions = grid.polygonize(0, connectivity).unwrap();
+
+    let (labels, geometries): (Vec<f64>, Vec<Geometry<f64>>) = regions
+        .into_iter()
+        .map(|(value, polygons)| (value, polygons.into()))
+        .unzip();
+    let mut round_trip = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    round_trip
+        .rasterize_geometries(&geometries, &labels, RasterizeOptions::default())
+        .unwrap();
+    assert!(grid
+        .data()
+        .iter()
+        .zip(round_trip.data())
+        .all(|(a, b)| a == b || a.is_nan() && b.is_nan()));
+}
@@ -0,0 +1,411 @@
+use geo::{line_string, point, polygon, Geometry, GeometryCollection, MultiPolygon};
+use rstest::rstest;
+use rurp::bounds::Bounds;
+use rurp::cell_value::CellValue;
+use rurp::grid::rasterize::{MergeMode, RasterizeOptions};
+use rurp::grid::Grid;
+pub mod utils;
+use utils::{build_empty_grid, STUB_BOUNDS};
+
+fn build_small_grid() -> Grid {
+    let bounds = Bounds::new(0., 0., 10., 10.).unwrap();
+    build_empty_grid(&bounds, 1., f64::NAN)
+}
+
+fn burned_cells(grid: &Grid) -> Vec<(usize, usize)> {
+    grid.data()
+        .indexed_iter()
+        .filter(|(_, value)| !value.is_nan())
+        .map(|((row, column, _), _)| (row, column))
+        .collect()
+}
+
+#[rstest]
+#[case(false, 3)]
+#[case(true, 15)]
+fn test_all_touched(#[case] all_touched: bool, #[case] expected_count: usize) {
+    let mut grid = build_small_grid();
+    let rectangle: Geometry =
+        polygon![(x: 2.6, y: 3.6), (x: 6.4, y: 3.6), (x: 6.4, y: 5.4), (x: 2.6, y: 5.4)].into();
+
+    grid.rasterize_geometries(
+        &[rectangle],
+        &[1.],
+        RasterizeOptions {
+            all_touched,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let cells = burned_cells(&grid);
+    assert_eq!(cells.len(), expected_count);
+    assert!(cells.contains(&(4, 3)) && cells.contains(&(4, 5)));
+    assert_eq!(cells.contains(&(3, 2)), all_touched);
+}
+
+#[rstest]
+fn test_polygon_with_hole() {
+    let mut grid = build_small_grid();
+    let square_with_hole: Geometry = polygon!(
+        exterior: [(x: 1., y: 1.), (x: 9., y: 1.), (x: 9., y: 9.), (x: 1., y: 9.)],
+        interiors: [[(x: 3., y: 3.), (x: 7., y: 3.), (x: 7., y: 7.), (x: 3., y: 7.)]],
+    )
+    .into();
+
+    grid.rasterize_geometries(&[square_with_hole], &[1.], RasterizeOptions::default())
+        .unwrap();
+
+    assert_eq!(burned_cells(&grid).len(), 64 - 16);
+    assert!(grid.data()[[5, 5, 0]].is_nan());
+    assert_eq!(grid.data()[[1, 1, 0]], 1.);
+}
+
+#[rstest]
+fn test_lines_and_points() {
+    let mut grid = build_small_grid();
+    let horizontal: Geometry = line_string![(x: 0.5, y: 2.5), (x: 8.5, y: 2.5)].into();
+    let diagonal: Geometry = line_string![(x: 0., y: 0.), (x: 10., y: 10.)].into();
+    let single_point: Geometry = point!(x: 7.5, y: 8.5).into();
+
+    grid.rasterize_geometries(
+        &[horizontal, diagonal, single_point],
+        &[1., 2., 3.],
+        RasterizeOptions::default(),
+    )
+    .unwrap();
+
+    (0..=8).for_each(|column| assert!(!grid.data()[[2, column, 0]].is_nan()));
+    (0..10).for_each(|cell| assert_eq!(grid.data()[[cell, cell, 0]], 2.));
+    assert_eq!(grid.data()[[8, 7, 0]], 3.);
+    assert!(grid.data()[[2, 9, 0]].is_nan());
+    assert!(grid.data()[[0, 9, 0]].is_nan());
+}
+
+#[rstest]
+#[case(MergeMode::Replace, [5., 5., 2.])]
+#[case(MergeMode::Add, [10., 7., 2.])]
+#[case(MergeMode::Max, [5., 5., 2.])]
+#[case(MergeMode::Min, [2., 2., 2.])]
+fn test_merge_modes(#[case] merge: MergeMode, #[case] expected: [f64; 3]) {
+    let mut grid = build_small_grid();
+    // The left half starts at 3, the right half at nodata.
+    grid.data_mut()
+        .indexed_iter_mut()
+        .filter(|((_, column, _), _)| *column < 5)
+        .for_each(|(_, value)| *value = 3.);
+    let first: Geometry =
+        polygon![(x: 0., y: 0.), (x: 10., y: 0.), (x: 10., y: 5.), (x: 0., y: 5.)].into();
+    let second: Geometry =
+        polygon![(x: 0., y: 0.), (x: 10., y: 0.), (x: 10., y: 3.), (x: 0., y: 3.)].into();
+
+    grid.rasterize_geometries(
+        &[first, second],
+        &[2., 5.],
+        RasterizeOptions {
+            merge,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // Cells covered by both polygons on the left, both on the right, and only the first on the right.
+    let cells = [(1, 2), (1, 7), (4, 7)];
+    for ((row, column), expected) in cells.into_iter().zip(expected) {
+        let expected = match (merge, column < 5) {
+            (MergeMode::Add, true) => 3. + 2. + 5.,
+            (MergeMode::Max, true) | (MergeMode::Min, true) if row < 3 => {
+                expected.max(if merge == MergeMode::Max {
+                    3.
+                } else {
+                    f64::MIN
+                })
+            }
+            _ => expected,
+        };
+        assert_eq!(grid.data()[[row, column, 0]], expected, "({row}, {column})");
+    }
+    // Cells not covered keep their values.
+    assert_eq!(grid.data()[[8, 2, 0]], 3.);
+    assert!(grid.data()[[8, 7, 0]].is_nan());
+}
+
+#[rstest]
+fn test_existing_data_is_kept() {
+    let mut grid = build_small_grid();
+    grid.data_mut().fill(7.);
+    let square = polygon![(x: 2., y: 2.), (x: 4., y: 2.), (x: 4., y: 4.), (x: 2., y: 4.)];
+
+    grid.rasterize_polygons(&[square], &[1.]).unwrap();
+
+    assert_eq!(grid.data()[[2, 2, 0]], 1.);
+    assert_eq!(grid.data()[[8, 8, 0]], 7.);
+    // The square covers 2 x 2 cells, and its edges at 4 touch the next row and column.
+    assert_eq!(
+        grid.data().iter().filter(|&&value| value == 7.).count(),
+        100 - 9
+    );
+}
+
+#[rstest]
+fn test_geometry_collection_burns_once() {
+    let mut grid = build_small_grid();
+    grid.data_mut().fill(0.);
+    let square = polygon![(x: 2., y: 2.), (x: 6., y: 2.), (x: 6., y: 6.), (x: 2., y: 6.)];
+    let line = line_string![(x: 0.5, y: 4.5), (x: 9.5, y: 4.5)];
+    let collection = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+        square.into(),
+        line.into(),
+    ]));
+
+    grid.rasterize_geometries(
+        &[collection],
+        &[1.],
+        RasterizeOptions {
+            merge: MergeMode::Add,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(grid.data()[[4, 4, 0]], 1.);
+    assert_eq!(grid.data()[[4, 9, 0]], 1.);
+    assert_eq!(grid.data()[[3, 3, 0]], 1.);
+    assert_eq!(grid.data()[[8, 8, 0]], 0.);
+}
+
+#[rstest]
+fn test_geometries_outside_of_grid() {
+    let mut grid = build_small_grid();
+    let outside: Geometry =
+        polygon![(x: 20., y: 20.), (x: 30., y: 20.), (x: 30., y: 30.), (x: 20., y: 30.)].into();
+    let partly: Geometry =
+        polygon![(x: -5., y: -5.), (x: 2., y: -5.), (x: 2., y: 2.), (x: -5., y: 2.)].into();
+
+    grid.rasterize_geometries(&[outside, partly], &[1., 2.], RasterizeOptions::default())
+        .unwrap();
+
+    assert_eq!(burned_cells(&grid), vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+}
+
+#[rstest]
+fn test_label_count_mismatch() {
+    let mut grid = build_small_grid();
+    let single_point: Geometry = point!(x: 1., y: 1.).into();
+
+    let result = grid.rasterize_geometries(&[single_point], &[1., 2.], RasterizeOptions::default());
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "There must be one label per geometry"
+    );
+}
+
+#[rstest]
+fn test_rasterize_mixed_geometries() {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    grid.data_mut().fill(0.);
+    let multi_polygon: Geometry = MultiPolygon::new(vec![
+        polygon!(
+            exterior: [(x: 5., y: 5.), (x: 45., y: 5.), (x: 45., y: 45.), (x: 5., y: 45.)],
+            interiors: [[(x: 15., y: 15.), (x: 35., y: 15.), (x: 35., y: 35.), (x: 15., y: 35.)]],
+        ),
+        polygon![(x: 60., y: 60.), (x: 95., y: 70.), (x: 70., y: 95.)],
+    ])
+    .into();
+    let river: Geometry =
+        line_string![(x: 0., y: 90.), (x: 30., y: 60.), (x: 50., y: 55.), (x: 100., y: 10.)].into();
+    let wells: Geometry =
+        geo::MultiPoint::from(vec![(10.5, 80.5), (20.5, 70.5), (80.5, 20.5)]).into();
+
+    grid.rasterize_geometries(
+        &[multi_polygon, river, wells],
+        &[1., 2., 4.],
+        RasterizeOptions {
+            merge: MergeMode::Add,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    utils::assert_grid_matches_snapshot(&grid, "test_rasterize_geometries_1");
+}
+
+#[rstest]
+fn test_rasterize_into_band() {
+    let bounds = Bounds::new(0., 0., 10., 10.).unwrap();
+    let mut grid = build_empty_grid(&bounds, 1., f64::NAN);
+    grid.reset_bands(3);
+    grid.data_mut().fill(7.);
+    let square: Geometry =
+        polygon![(x: 2., y: 2.), (x: 4., y: 2.), (x: 4., y: 4.), (x: 2., y: 4.)].into();
+
+    grid.rasterize_geometries(
+        &[square],
+        &[1.],
+        RasterizeOptions {
+            band: 2,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(grid.data()[[2, 2, 2]], 1.);
+    assert_eq!(grid.data()[[2, 2, 0]], 7.);
+    assert_eq!(grid.data()[[2, 2, 1]], 7.);
+    assert_eq!(grid.data()[[8, 8, 2]], 7.);
+}
+
+#[rstest]
+#[case(1)]
+#[case(5)]
+fn test_band_out_of_range(#[case] band: usize) {
+    let mut grid = build_small_grid();
+    let single_point: Geometry = point!(x: 1., y: 1.).into();
+
+    let result = grid.rasterize_geometries(
+        &[single_point],
+        &[1.],
+        RasterizeOptions {
+            band,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(result.unwrap_err().to_string(), "Band is out of range");
+}
+
+#[rstest]
+fn test_rasterize_polygons_label_count_mismatch() {
+    let mut grid = build_small_grid();
+    let square = polygon![(x: 2., y: 2.), (x: 4., y: 2.), (x: 4., y: 4.), (x: 2., y: 4.)];
+
+    let result = grid.rasterize_polygons(&[square.clone(), square], &[1.]);
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "There must be one label per geometry"
+    );
+    assert!(burned_cells(&grid).is_empty());
+}
+
+#[rstest]
+#[case((4, 2), 0.25 * 0.25)]
+#[case((4, 3), 0.25)]
+#[case((3, 3), 1.)]
+#[case((3, 4), 0.75)]
+#[case((2, 4), 0.75 * 0.75)]
+#[case((5, 3), 0.)]
+fn test_coverage_fractions(#[case] cell: (usize, usize), #[case] expected: f64) {
+    let (row, column) = cell;
+    let grid = build_small_grid();
+    let square: Geometry =
+        polygon![(x: 2.75, y: 2.25), (x: 4.75, y: 2.25), (x: 4.75, y: 4.25), (x: 2.75, y: 4.25)]
+            .into();
+
+    let fractions = grid.coverage_fractions(&square).unwrap();
+
+    assert!(
+        (fractions[[row, column]] - expected).abs() < 1e-9,
+        "{}",
+        fractions[[row, column]]
+    );
+    assert!((fractions.sum() - 4.).abs() < 1e-9);
+}
+
+#[rstest]
+fn test_coverage_fractions_of_rotated_polygon_with_hole() {
+    let grid = build_small_grid();
+    let diamond: Geometry = polygon!(
+        exterior: [(x: 5., y: 0.5), (x: 9.5, y: 5.), (x: 5., y: 9.5), (x: 0.5, y: 5.)],
+        interiors: [[(x: 4.2, y: 4.2), (x: 5.8, y: 4.2), (x: 5.8, y: 5.8), (x: 4.2, y: 5.8)]],
+    )
+    .into();
+
+    let fractions = grid.coverage_fractions(&diamond).unwrap();
+
+    let expected_area = 4.5 * 4.5 * 2. - 1.6 * 1.6;
+    assert!((fractions.sum() - expected_area).abs() < 1e-9);
+    assert!(fractions
+        .iter()
+        .all(|&fraction| (0. ..=1.).contains(&fraction)));
+    assert!((fractions[[5, 5]] - (1. - 0.8 * 0.8)).abs() < 1e-9);
+    assert_eq!(fractions[[0, 0]], 0.);
+}
+
+#[rstest]
+fn test_coverage_fractions_require_polygons() {
+    let mut grid = build_small_grid();
+    let line: Geometry = line_string![(x: 0.5, y: 2.5), (x: 8.5, y: 2.5)].into();
+
+    assert_eq!(
+        grid.coverage_fractions(&line).unwrap_err().to_string(),
+        "Coverage fractions require polygonal geometries"
+    );
+    let result = grid.rasterize_geometries(
+        &[line],
+        &[1.],
+        RasterizeOptions {
+            coverage_fraction: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Coverage fractions require polygonal geometries"
+    );
+}
+
+#[rstest]
+fn test_rasterize_coverage_fractions() {
+    let mut grid = build_small_grid();
+    grid.data_mut().fill(0.);
+    let left: Geometry =
+        polygon![(x: 0., y: 0.), (x: 5.5, y: 0.), (x: 5.5, y: 10.), (x: 0., y: 10.)].into();
+    let right: Geometry =
+        polygon![(x: 5.5, y: 0.), (x: 10., y: 0.), (x: 10., y: 10.), (x: 5.5, y: 10.)].into();
+
+    grid.rasterize_geometries(
+        &[left, right],
+        &[2., 4.],
+        RasterizeOptions {
+            merge: MergeMode::Add,
+            coverage_fraction: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(grid.data()[[3, 4, 0]], 2.);
+    assert_eq!(grid.data()[[3, 5, 0]], 0.5 * 2. + 0.5 * 4.);
+    assert_eq!(grid.data()[[3, 6, 0]], 4.);
+}
+
+/// Burn two overlapping squares with `label` into a typed grid with `MergeMode::Add`,
+/// returning the value where they overlap.
+fn add_overlapping_squares<T: CellValue>(label: T, nodata: T) -> T {
+    let bounds = Bounds::new(0., 0., 10., 10.).unwrap();
+    let mut grid = build_empty_grid(&bounds, 1., nodata);
+    let square: Geometry =
+        polygon![(x: 2., y: 2.), (x: 6., y: 2.), (x: 6., y: 6.), (x: 2., y: 6.)].into();
+
+    grid.rasterize_geometries(
+        &[square.clone(), square],
+        &[label, label],
+        RasterizeOptions {
+            merge: MergeMode::Add,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    grid.data()[[3, 3, 0]]
+}
+
+#[rstest]
+fn test_add_saturates_integer_grids() {
+    assert_eq!(add_overlapping_squares(200_u8, 0), u8::MAX);
+    assert_eq!(add_overlapping_squares(100_u8, 0), 200);
+    assert_eq!(add_overlapping_squares(i32::MAX - 1, i32::MIN), i32::MAX);
+    assert_eq!(add_overlapping_squares(-5_i32, i32::MIN), -10);
+}
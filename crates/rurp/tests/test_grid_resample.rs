@@ -0,0 +1,143 @@
+use ndarray::s;
+use rstest::rstest;
+use rurp::bounds::Bounds;
+use rurp::grid::resample::ResampleMethod;
+use rurp::resolution::{ExtentPolicy, Resolution};
+pub mod utils;
+use utils::{build_empty_grid, build_plane_grid, build_stub_points, CONUS_BOUNDS, STUB_BOUNDS};
+
+#[rstest]
+#[case(ResampleMethod::Nearest, 10.)]
+#[case(ResampleMethod::Bilinear, 7.5)]
+#[case(ResampleMethod::Cubic, 7.5)]
+#[case(ResampleMethod::Average, 7.5)]
+fn test_resample_coarser(#[case] method: ResampleMethod, #[case] expected_origin: f64) {
+    let grid = build_plane_grid();
+
+    let resampled = grid
+        .resample(
+            &STUB_BOUNDS,
+            &Resolution::square(4.).unwrap(),
+            ExtentPolicy::Expand,
+            method,
+        )
+        .unwrap();
+
+    assert_eq!(resampled.height(), 25);
+    assert_eq!(resampled.width(), 25);
+    assert_eq!(resampled.bands(), 1);
+    for ((row, column, _), value) in resampled.data().indexed_iter() {
+        let expected = expected_origin + 8. * column as f64 + 12. * row as f64;
+        assert!((value - expected).abs() < 1e-9);
+    }
+}
+
+#[rstest]
+fn test_resample_average_ignores_nodata() {
+    let mut grid = build_plane_grid();
+    grid.data_mut()[[0, 0, 0]] = f64::NAN;
+    grid.data_mut()
+        .slice_mut(ndarray::s![4..8, 4..8, 0])
+        .fill(f64::NAN);
+
+    let resampled = grid
+        .resample(
+            &STUB_BOUNDS,
+            &Resolution::square(4.).unwrap(),
+            ExtentPolicy::Expand,
+            ResampleMethod::Average,
+        )
+        .unwrap();
+    let bilinear = grid
+        .resample(
+            &STUB_BOUNDS,
+            &Resolution::square(4.).unwrap(),
+            ExtentPolicy::Expand,
+            ResampleMethod::Bilinear,
+        )
+        .unwrap();
+
+    assert!((resampled.data()[[0, 0, 0]] - 8.).abs() < 1e-9);
+    assert!(resampled.data()[[1, 1, 0]].is_nan());
+    assert!(!bilinear.data()[[0, 0, 0]].is_nan());
+    assert!(bilinear.data()[[1, 1, 0]].is_nan());
+}
+
+#[rstest]
+fn test_resample_outside_source_is_nodata() {
+    let grid = build_plane_grid();
+    let bounds = Bounds::new(50., 50., 150., 150.).unwrap();
+
+    let resampled = grid
+        .resample(
+            &bounds,
+            &Resolution::square(10.).unwrap(),
+            ExtentPolicy::Expand,
+            ResampleMethod::Average,
+        )
+        .unwrap();
+
+    assert!(!resampled.data()[[0, 0, 0]].is_nan());
+    assert!(resampled.data()[[9, 9, 0]].is_nan());
+    assert!(resampled.data()[[0, 9, 0]].is_nan());
+}
+
+#[rstest]
+fn test_resample_into_keeps_bands_and_target_nodata() {
+    let mut grid = build_plane_grid();
+    grid.reset_bands(2);
+    grid.data_mut()
+        .indexed_iter_mut()
+        .for_each(|((_, _, band), value)| *value = band as f64 + 1.);
+    // Source nodata covers every source cell under the first target cell of the second band.
+    grid.data_mut().slice_mut(s![0..2, 0..2, 1]).fill(f64::NAN);
+    let mut target = build_empty_grid(&STUB_BOUNDS, 2., -9999.);
+
+    grid.resample_into(&mut target, ResampleMethod::Nearest);
+
+    assert_eq!(target.bands(), 2);
+    assert_eq!(target.data()[[0, 0, 0]], 1.);
+    assert_eq!(target.data()[[0, 0, 1]], -9999.);
+    assert_eq!(target.data()[[1, 1, 1]], 2.);
+    assert!(target.data().iter().all(|value| !value.is_nan()));
+}
+
+#[rstest]
+#[case(1, ResampleMethod::Nearest)]
+#[case(2, ResampleMethod::Average)]
+fn test_resample(#[case] case_number: usize, #[case] method: ResampleMethod) {
+    let mut grid = build_empty_grid(&CONUS_BOUNDS, 8000., f64::NAN);
+    let points = build_stub_points(&CONUS_BOUNDS, &2000);
+    rurp::interpolate::inverse_distance_weighting::interpolate(&mut grid, &points, 2., 250_000., 0);
+
+    let resampled = grid
+        .resample(
+            &CONUS_BOUNDS,
+            &Resolution::square(32000.).unwrap(),
+            ExtentPolicy::Expand,
+            method,
+        )
+        .unwrap();
+
+    utils::assert_grid_matches_snapshot(&resampled, &format!("test_resample_{}", case_number));
+}
+
+#[rstest]
+#[case(ExtentPolicy::Expand, Some(34))]
+#[case(ExtentPolicy::Shrink, Some(33))]
+#[case(ExtentPolicy::Exact, None)]
+fn test_resample_extent_policy(#[case] policy: ExtentPolicy, #[case] expected: Option<usize>) {
+    let grid = build_plane_grid();
+    let resolution = Resolution::square(3.).unwrap();
+
+    let resampled = grid.resample(&STUB_BOUNDS, &resolution, policy, ResampleMethod::Nearest);
+
+    match expected {
+        Some(cells) => {
+            let resampled = resampled.unwrap();
+            assert_eq!((resampled.height(), resampled.width()), (cells, cells));
+            assert_eq!(resampled.cell_width(), 3.);
+        }
+        None => assert!(resampled.is_err()),
+    }
+}
@@ -0,0 +1,73 @@
+use rstest::rstest;
+use rurp::grid::sample::SampleMethod;
+use rurp::point::Point;
+pub mod utils;
+use utils::build_plane_grid;
+
+#[rstest]
+#[case(SampleMethod::Nearest, 10.2, 20.7, 80.)]
+#[case(SampleMethod::Nearest, 100., 100., 495.)]
+#[case(SampleMethod::Bilinear, 10.5, 20.5, 80.)]
+#[case(SampleMethod::Bilinear, 10.2, 20.7, 80.)]
+#[case(SampleMethod::Bilinear, 0.2, 0.2, 0.)]
+#[case(SampleMethod::Bicubic, 10.5, 20.5, 80.)]
+#[case(SampleMethod::Bicubic, 42.25, 61.75, 267.25)]
+fn test_sample(
+    #[case] method: SampleMethod,
+    #[case] x: f64,
+    #[case] y: f64,
+    #[case] expected: f64,
+) {
+    let grid = build_plane_grid();
+
+    let value = grid.sample(x, y, 0, method);
+
+    assert!((value - expected).abs() < 1e-9);
+}
+
+#[rstest]
+#[case(SampleMethod::Nearest)]
+#[case(SampleMethod::Bilinear)]
+#[case(SampleMethod::Bicubic)]
+fn test_sample_outside_is_nodata(#[case] method: SampleMethod) {
+    let grid = build_plane_grid();
+
+    assert!(grid.sample(-0.1, 50., 0, method).is_nan());
+    assert!(grid.sample(50., 100.1, 0, method).is_nan());
+}
+
+#[rstest]
+#[case(SampleMethod::Nearest, 30.2, 30.2, false)]
+#[case(SampleMethod::Nearest, 31.2, 31.2, true)]
+#[case(SampleMethod::Bilinear, 31.2, 31.2, false)]
+#[case(SampleMethod::Bilinear, 31.5, 31.5, true)]
+#[case(SampleMethod::Bicubic, 32.2, 32.2, false)]
+#[case(SampleMethod::Bicubic, 32.5, 32.5, true)]
+fn test_sample_honors_nodata(
+    #[case] method: SampleMethod,
+    #[case] x: f64,
+    #[case] y: f64,
+    #[case] expected_valid: bool,
+) {
+    let mut grid = build_plane_grid();
+    grid.data_mut()[[30, 30, 0]] = f64::NAN;
+
+    assert_eq!(!grid.sample(x, y, 0, method).is_nan(), expected_valid);
+}
+
+#[rstest]
+fn test_sample_points() {
+    let grid = build_plane_grid();
+    let points = vec![
+        Point::new(10.5, 20.5, vec![]),
+        Point::new(-5., 20.5, vec![]),
+        Point::new(99.5, 0.5, vec![]),
+    ];
+
+    let values = grid.sample_points(&points, 0, SampleMethod::Bilinear);
+
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[0], 80.);
+    assert!(values[1].is_nan());
+    assert_eq!(values[2], 198.);
+}
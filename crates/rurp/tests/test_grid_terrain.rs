@@ -0,0 +1,122 @@
+use rstest::rstest;
+use rurp::bounds::Bounds;
+use rurp::grid::terrain::GradientMethod;
+use rurp::grid::Grid;
+use rurp::interpolate::inverse_distance_weighting::interpolate;
+pub mod utils;
+use utils::{assert_close, build_empty_grid, build_stub_points, build_world_grid, STUB_BOUNDS};
+
+/// Build a 10 x 10 grid of cells 2 world units wide filled by a function of the cell center.
+fn build_surface(elevation: impl Fn(f64, f64) -> f64) -> Grid {
+    let bounds = Bounds::new(0., 0., 20., 20.).unwrap();
+    build_world_grid(&bounds, 2., elevation)
+}
+
+#[rstest]
+#[case(GradientMethod::Horn, |x: f64, _: f64| 2. * x, 2_f64.atan().to_degrees(), 270.)]
+#[case(GradientMethod::ZevenbergenThorne, |x: f64, _: f64| 2. * x, 2_f64.atan().to_degrees(), 270.)]
+#[case(GradientMethod::Horn, |_: f64, y: f64| y, 45., 180.)]
+#[case(GradientMethod::ZevenbergenThorne, |x: f64, y: f64| -x - y, 2_f64.sqrt().atan().to_degrees(), 45.)]
+fn test_slope_and_aspect_of_planes(
+    #[case] method: GradientMethod,
+    #[case] elevation: fn(f64, f64) -> f64,
+    #[case] expected_slope: f64,
+    #[case] expected_aspect: f64,
+) {
+    let surface = build_surface(elevation);
+
+    let slope = surface.slope(method);
+    let aspect = surface.aspect(method);
+
+    for (row, column) in [(1, 1), (5, 4), (8, 8)] {
+        assert_close(slope.data()[[row, column, 0]], expected_slope);
+        assert_close(aspect.data()[[row, column, 0]], expected_aspect);
+    }
+    assert_eq!(slope.bounds(), surface.bounds());
+}
+
+#[rstest]
+fn test_flat_surface() {
+    let surface = build_surface(|_, _| 3.);
+
+    let slope = surface.slope(GradientMethod::Horn);
+    let aspect = surface.aspect(GradientMethod::Horn);
+    let hillshade = surface.hillshade(GradientMethod::Horn, 315., 30.).unwrap();
+    let curvature = surface.curvature();
+
+    assert!(slope.data().iter().all(|&value| value == 0.));
+    assert!(aspect.data().iter().all(|value| value.is_nan()));
+    assert!(hillshade
+        .data()
+        .iter()
+        .all(|&value| (value - 0.5).abs() < 1e-9));
+    assert!(curvature.data().iter().all(|&value| value == 0.));
+}
+
+#[rstest]
+#[case(0., 1.)]
+#[case(180., 0.)]
+#[case(90., 0.5)]
+fn test_hillshade(#[case] azimuth: f64, #[case] expected: f64) {
+    // A 45 degree slope facing north, lit from 45 degrees above the horizon.
+    let surface = build_surface(|_, y| -y);
+
+    let hillshade = surface
+        .hillshade(GradientMethod::ZevenbergenThorne, azimuth, 45.)
+        .unwrap();
+
+    assert_close(hillshade.data()[[5, 5, 0]], expected);
+}
+
+#[rstest]
+#[case(|x: f64, y: f64| (x - 10.).powi(2) + (y - 10.).powi(2), -4.)]
+#[case(|x: f64, y: f64| -0.5 * (x - 10.).powi(2) - 0.25 * (y - 10.).powi(2), 1.5)]
+#[case(|x: f64, _: f64| 3. * x, 0.)]
+fn test_curvature(#[case] elevation: fn(f64, f64) -> f64, #[case] expected: f64) {
+    let surface = build_surface(elevation);
+
+    let curvature = surface.curvature();
+
+    for (row, column) in [(1, 1), (5, 4), (8, 8)] {
+        assert_close(curvature.data()[[row, column, 0]], expected);
+    }
+}
+
+#[rstest]
+fn test_nodata_and_edges() {
+    let mut surface = build_surface(|x, _| x);
+    surface.data_mut()[[5, 5, 0]] = f64::NAN;
+
+    let slope = surface.slope(GradientMethod::Horn);
+
+    assert!(slope.data()[[5, 5, 0]].is_nan());
+    assert_eq!(
+        slope.data().iter().filter(|value| value.is_nan()).count(),
+        1
+    );
+    // The missing neighbor to the east takes the value of the cell, halving the difference.
+    assert_close(slope.data()[[5, 9, 0]], 0.5_f64.atan().to_degrees());
+}
+
+#[rstest]
+#[case(315., -1.)]
+#[case(315., 91.)]
+#[case(f64::NAN, 45.)]
+fn test_hillshade_errors(#[case] azimuth: f64, #[case] altitude: f64) {
+    let surface = build_surface(|x, _| x);
+
+    let result = surface.hillshade(GradientMethod::Horn, azimuth, altitude);
+
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn test_hillshade_of_interpolation() {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    let points = build_stub_points(&STUB_BOUNDS, &50);
+    interpolate(&mut grid, &points, 2., 30., 0);
+
+    let hillshade = grid.hillshade(GradientMethod::Horn, 315., 45.).unwrap();
+
+    utils::assert_grid_matches_snapshot(&hillshade, "test_hillshade_1");
+}
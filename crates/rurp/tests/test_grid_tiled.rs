@@ -0,0 +1,201 @@
+use rstest::rstest;
+use rurp::bounds::Bounds;
+use rurp::equivalent;
+use rurp::grid::tiled::{RawFileSink, TiledGrid};
+use rurp::grid::{Grid, Registration};
+use rurp::interpolate::inverse_distance_weighting::interpolate;
+use rurp::resolution::{ExtentPolicy, Resolution};
+use std::env;
+use std::error::Error;
+pub mod utils;
+use utils::{build_empty_grid, build_stub_points, CONUS_BOUNDS, STUB_BOUNDS};
+
+fn build_tiled_grid(
+    bounds: &Bounds,
+    resolution: f64,
+    tile_size: (usize, usize),
+    halo: usize,
+) -> Result<TiledGrid, Box<dyn Error>> {
+    let resolution = Resolution::square(resolution)?;
+    TiledGrid::from_resolution(
+        bounds,
+        &resolution,
+        ExtentPolicy::Expand,
+        Registration::PixelIsArea,
+        f64::NAN,
+        tile_size,
+        halo,
+    )
+}
+
+#[rstest]
+#[case(&*STUB_BOUNDS, 1., (30, 40), 0, 4, 3)]
+#[case(&*STUB_BOUNDS, 1., (30, 40), 5, 4, 3)]
+#[case(&*STUB_BOUNDS, 1., (100, 100), 5, 1, 1)]
+#[case(&*CONUS_BOUNDS, 16000., (64, 64), 2, 3, 6)]
+fn test_tile_layout(
+    #[case] bounds: &Bounds,
+    #[case] resolution: f64,
+    #[case] tile_size: (usize, usize),
+    #[case] halo: usize,
+    #[case] expected_tile_rows: usize,
+    #[case] expected_tile_columns: usize,
+) {
+    let tiled_grid = build_tiled_grid(bounds, resolution, tile_size, halo).unwrap();
+    let grid = build_empty_grid(bounds, resolution, f64::NAN);
+
+    assert_eq!(tiled_grid.height(), grid.height());
+    assert_eq!(tiled_grid.width(), grid.width());
+    assert_eq!(tiled_grid.tile_rows(), expected_tile_rows);
+    assert_eq!(tiled_grid.tile_columns(), expected_tile_columns);
+
+    let mut owners = vec![0; grid.height() * grid.width()];
+    for tile in tiled_grid.tiles() {
+        let tile = tile.unwrap();
+        assert_eq!(
+            tile.rows().start,
+            tile.core_rows().start.saturating_sub(halo)
+        );
+        assert_eq!(
+            tile.columns().end,
+            (tile.core_columns().end + halo).min(grid.width())
+        );
+        assert_eq!(
+            tile.grid().data().shape(),
+            &[tile.rows().len(), tile.columns().len(), 1]
+        );
+        assert_eq!(
+            tile.core().shape(),
+            &[tile.core_rows().len(), tile.core_columns().len(), 1]
+        );
+
+        for (i, row) in tile.rows().enumerate() {
+            for (j, column) in tile.columns().enumerate() {
+                let tile_center = (tile.grid().x()[[i, j]], tile.grid().y()[[i, j]]);
+                let center = (grid.x()[[row, column]], grid.y()[[row, column]]);
+                assert!((tile_center.0 - center.0).abs() < 1e-6 * grid.cell_width());
+                assert!((tile_center.1 - center.1).abs() < 1e-6 * grid.cell_height());
+            }
+        }
+        for row in tile.core_rows() {
+            for column in tile.core_columns() {
+                owners[row * grid.width() + column] += 1;
+            }
+        }
+    }
+    assert!(owners.iter().all(|&owner_count| owner_count == 1));
+}
+
+#[rstest]
+#[case(&*STUB_BOUNDS, 1., 100, 25., (30, 40), 0)]
+#[case(&*STUB_BOUNDS, 1., 100, 25., (17, 23), 3)]
+#[case(&*CONUS_BOUNDS, 16000., 10000, 250_000., (64, 100), 4)]
+fn test_assemble_matches_whole_grid(
+    #[case] bounds: &Bounds,
+    #[case] resolution: f64,
+    #[case] point_count: usize,
+    #[case] radius: f64,
+    #[case] tile_size: (usize, usize),
+    #[case] halo: usize,
+) {
+    let points = build_stub_points(bounds, &point_count);
+    let mut grid = build_empty_grid(bounds, resolution, f64::NAN);
+    interpolate(&mut grid, &points, 2., radius, 0);
+
+    let tiled_grid = build_tiled_grid(bounds, resolution, tile_size, halo).unwrap();
+    let assembled = tiled_grid
+        .assemble(|tile| {
+            interpolate(tile, &points, 2., radius, 0);
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(assembled.data().shape(), grid.data().shape());
+    assert_eq!(assembled.bounds(), grid.bounds());
+    for (value, expected) in assembled.data().iter().zip(grid.data()) {
+        assert!(
+            equivalent(value, expected) || (value - expected).abs() < 1e-9,
+            "{value} != {expected}"
+        );
+    }
+}
+
+#[rstest]
+fn test_raw_file_sink() {
+    let bounds = Bounds::new(0., 0., 50., 30.).unwrap();
+    let resolution = Resolution::square(1.).unwrap();
+    let tiled_grid = TiledGrid::from_resolution(
+        &bounds,
+        &resolution,
+        ExtentPolicy::Exact,
+        Registration::PixelIsPoint,
+        f32::NAN,
+        (8, 12),
+        2,
+    )
+    .unwrap();
+    let fill = |tile: &mut Grid<f32>| {
+        tile.reset_bands(2);
+        tile.iter_world_bands_mut().for_each(|(x, y, mut values)| {
+            values[0] = (x * 100. + y) as f32;
+            values[1] = -y as f32;
+        });
+        Ok(())
+    };
+
+    let file_path = env::temp_dir().join("rurp_test_raw_file_sink.bin");
+    let mut sink = RawFileSink::create(&file_path, &tiled_grid, 2).unwrap();
+    tiled_grid.process(fill, &mut sink).unwrap();
+    sink.finish().unwrap();
+    let bytes = std::fs::read(&file_path).unwrap();
+    std::fs::remove_file(&file_path).unwrap();
+
+    let assembled = tiled_grid.assemble(fill).unwrap();
+    assert_eq!(assembled.data().shape(), &[31, 51, 2]);
+    let expected: Vec<u8> = assembled
+        .data()
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+    assert_eq!(bytes, expected);
+    assert_eq!(assembled.data()[[0, 0, 0]], 0.);
+    assert_eq!(assembled.data()[[30, 50, 0]], 5030.);
+}
+
+#[rstest]
+fn test_raw_file_sink_band_mismatch() {
+    let tiled_grid = build_tiled_grid(&STUB_BOUNDS, 10., (5, 5), 0).unwrap();
+    let file_path = env::temp_dir().join("rurp_test_raw_file_sink_band_mismatch.bin");
+    let mut sink = RawFileSink::create(&file_path, &tiled_grid, 2).unwrap();
+
+    let result = tiled_grid.process(|_| Ok(()), &mut sink);
+    std::fs::remove_file(&file_path).unwrap();
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "tile band count does not match the file"
+    );
+}
+
+#[rstest]
+#[case((0, 10))]
+#[case((10, 0))]
+fn test_zero_tile_size(#[case] tile_size: (usize, usize)) {
+    let result = build_tiled_grid(&STUB_BOUNDS, 1., tile_size, 0);
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "tile size must be positive"
+    );
+}
+
+#[rstest]
+fn test_tile_index_out_of_range() {
+    let tiled_grid = build_tiled_grid(&STUB_BOUNDS, 1., (50, 50), 0).unwrap();
+
+    assert!(tiled_grid.tile(3).is_ok());
+    assert_eq!(
+        tiled_grid.tile(4).unwrap_err().to_string(),
+        "tile index is out of range"
+    );
+}
@@ -0,0 +1,226 @@
+use geo::{line_string, polygon, Geometry};
+use rstest::rstest;
+use rurp::bounds::Bounds;
+use rurp::grid::zonal::{ZonalOptions, ZoneStatistics};
+use rurp::grid::Grid;
+pub mod utils;
+use utils::{assert_close, build_indexed_grid};
+
+/// Build a 10 x 10 grid of unit cells where each cell holds its column index.
+fn build_column_grid() -> Grid {
+    let bounds = Bounds::new(0., 0., 10., 10.).unwrap();
+    build_indexed_grid(&bounds, 1., |_, column| column as f64)
+}
+
+#[rstest]
+fn test_zonal_statistics() {
+    let grid = build_column_grid();
+    // Touches columns 2 to 5 of rows 0 to 1.
+    let zone: Geometry =
+        polygon![(x: 2.25, y: 0.25), (x: 5.75, y: 0.25), (x: 5.75, y: 1.75), (x: 2.25, y: 1.75)]
+            .into();
+
+    let statistics = grid
+        .zonal_statistics(
+            &[zone],
+            &ZonalOptions {
+                percentiles: vec![0., 25., 50., 100.],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    let statistics = statistics[0].as_ref().unwrap();
+    assert_eq!(statistics.count, 8);
+    assert_eq!(statistics.weight, 8.);
+    assert_eq!(statistics.sum, 2. * (2. + 3. + 4. + 5.));
+    assert_eq!(statistics.mean, 3.5);
+    assert_eq!(statistics.min, 2.);
+    assert_eq!(statistics.max, 5.);
+    assert_close(statistics.stddev, 1.25_f64.sqrt());
+    // The sorted values are 2, 2, 3, 3, 4, 4, 5, 5, positioned at 1/16, 3/16, ..., 15/16.
+    assert_eq!(statistics.percentiles, vec![2., 2.5, 3.5, 5.]);
+}
+
+#[rstest]
+fn test_zones_are_independent() {
+    let grid = build_column_grid();
+    let left: Geometry =
+        polygon![(x: 0., y: 0.), (x: 5.75, y: 0.), (x: 5.75, y: 9.75), (x: 0., y: 9.75)].into();
+    let right: Geometry =
+        polygon![(x: 4.25, y: 0.), (x: 10., y: 0.), (x: 10., y: 9.75), (x: 4.25, y: 9.75)].into();
+    let outside: Geometry =
+        polygon![(x: 20., y: 0.), (x: 30., y: 0.), (x: 30., y: 10.), (x: 20., y: 10.)].into();
+
+    let statistics = grid
+        .zonal_statistics(&[left, outside, right], &ZonalOptions::default())
+        .unwrap();
+
+    let means: Vec<Option<f64>> = statistics
+        .iter()
+        .map(|statistics| statistics.as_ref().map(|statistics| statistics.mean))
+        .collect();
+    assert_eq!(means, vec![Some(2.5), None, Some(6.5)]);
+    assert_eq!(statistics[0].as_ref().unwrap().count, 60);
+}
+
+#[rstest]
+fn test_zone_cells_match_rasterize_polygons() {
+    let grid = build_column_grid();
+    let zone = polygon![(x: 1.3, y: 0.6), (x: 8.2, y: 2.1), (x: 6.4, y: 7.9), (x: 2., y: 5.)];
+    let mut burned = build_indexed_grid(&Bounds::new(0., 0., 10., 10.).unwrap(), 1., |_, _| 0.);
+    burned
+        .rasterize_polygons(std::slice::from_ref(&zone), &[1.])
+        .unwrap();
+
+    let statistics = grid
+        .zonal_statistics(&[zone.into()], &ZonalOptions::default())
+        .unwrap();
+
+    let (count, sum) = burned
+        .data()
+        .indexed_iter()
+        .filter(|(_, &value)| value == 1.)
+        .fold((0, 0.), |(count, sum), ((_, column, _), _)| {
+            (count + 1, sum + column as f64)
+        });
+    let statistics = statistics[0].as_ref().unwrap();
+    assert_eq!(statistics.count, count);
+    assert_eq!(statistics.sum, sum);
+}
+
+#[rstest]
+fn test_nodata_is_ignored() {
+    let mut grid = build_column_grid();
+    grid.data_mut()
+        .indexed_iter_mut()
+        .filter(|((row, _, _), _)| *row >= 5)
+        .for_each(|(_, value)| *value = f64::NAN);
+    let zone: Geometry =
+        polygon![(x: 0., y: 4.25), (x: 10., y: 4.25), (x: 10., y: 10.), (x: 0., y: 10.)].into();
+    let empty: Geometry =
+        polygon![(x: 0., y: 6.25), (x: 10., y: 6.25), (x: 10., y: 10.), (x: 0., y: 10.)].into();
+
+    let statistics = grid
+        .zonal_statistics(&[zone, empty], &ZonalOptions::default())
+        .unwrap();
+
+    assert_eq!(statistics[0].as_ref().unwrap().count, 10);
+    assert_eq!(statistics[0].as_ref().unwrap().mean, 4.5);
+    assert_eq!(statistics[1], None);
+}
+
+#[rstest]
+fn test_small_zone_with_coverage_fractions() {
+    let grid = build_column_grid();
+    // Touches columns 3 and 4, covering a quarter of the one and three quarters of the other.
+    let zone: Geometry =
+        polygon![(x: 3.75, y: 2.), (x: 4.75, y: 2.), (x: 4.75, y: 2.4), (x: 3.75, y: 2.4)].into();
+
+    let touched = grid
+        .zonal_statistics(std::slice::from_ref(&zone), &ZonalOptions::default())
+        .unwrap();
+    let fractions = grid
+        .zonal_statistics(
+            &[zone],
+            &ZonalOptions {
+                coverage_fraction: true,
+                percentiles: vec![0., 20., 100.],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    // Without coverage fractions every touched cell counts fully.
+    let touched = touched[0].as_ref().unwrap();
+    assert_eq!((touched.count, touched.weight, touched.mean), (2, 2., 3.5));
+    let ZoneStatistics {
+        count,
+        weight,
+        sum,
+        mean,
+        min,
+        max,
+        stddev,
+        percentiles,
+    } = fractions[0].clone().unwrap();
+    assert_eq!(count, 2);
+    assert_close(weight, 0.4);
+    assert_close(sum, 0.1 * 3. + 0.3 * 4.);
+    assert_close(mean, 3.75);
+    assert_eq!((min, max), (3., 4.));
+    assert_close(stddev, 0.1875_f64.sqrt());
+    // The value of 3 is positioned at 0.05 / 0.4 and the value of 4 at 0.25 / 0.4.
+    assert_eq!(percentiles.len(), 3);
+    assert_eq!((percentiles[0], percentiles[2]), (3., 4.));
+    assert_close(percentiles[1], 3.15);
+}
+
+#[rstest]
+#[case(5., 2.)]
+#[case(25., 2.5)]
+#[case(50., 19. / 6.)]
+#[case(90., 4.)]
+fn test_weighted_percentiles(#[case] percentile: f64, #[case] expected: f64) {
+    let grid = build_column_grid();
+    // Covers a quarter of column 2, all of column 3 and half of column 4 in row 2.
+    let zone: Geometry =
+        polygon![(x: 2.75, y: 2.), (x: 4.5, y: 2.), (x: 4.5, y: 3.), (x: 2.75, y: 3.)].into();
+
+    let statistics = grid
+        .zonal_statistics(
+            &[zone],
+            &ZonalOptions {
+                coverage_fraction: true,
+                percentiles: vec![percentile],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    // The values 2, 3 and 4 weigh 0.25, 1 and 0.5, and are positioned
+    // at 0.125 / 1.75, 0.75 / 1.75 and 1.5 / 1.75.
+    assert_close(statistics[0].as_ref().unwrap().percentiles[0], expected);
+}
+
+#[rstest]
+fn test_zonal_statistics_of_other_band() {
+    let mut grid = build_column_grid();
+    grid.reset_bands(2);
+    grid.data_mut()
+        .indexed_iter_mut()
+        .for_each(|((row, _, band), value)| *value = (row * band) as f64);
+    let zone: Geometry =
+        polygon![(x: 0., y: 0.), (x: 10., y: 0.), (x: 10., y: 1.75), (x: 0., y: 1.75)].into();
+
+    let statistics = grid
+        .zonal_statistics(
+            &[zone],
+            &ZonalOptions {
+                band: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    assert_eq!(statistics[0].as_ref().unwrap().mean, 0.5);
+}
+
+#[rstest]
+#[case(ZonalOptions { band: 1, ..Default::default() }, "Band is out of range")]
+#[case(
+    ZonalOptions { percentiles: vec![50., 101.], ..Default::default() },
+    "Percentiles must be between 0 and 100"
+)]
+#[case(
+    ZonalOptions { coverage_fraction: true, ..Default::default() },
+    "Coverage fractions require polygonal geometries"
+)]
+fn test_zonal_statistics_errors(#[case] options: ZonalOptions, #[case] expected: &str) {
+    let grid = build_column_grid();
+    let line: Geometry = line_string![(x: 0.5, y: 2.5), (x: 8.5, y: 2.5)].into();
+
+    let result = grid.zonal_statistics(&[line], &options);
+
+    assert_eq!(result.unwrap_err().to_string(), expected);
+}
@@ -2,8 +2,9 @@ use rstest::rstest;
 use rurp::bounds::Bounds;
 use rurp::grid::Grid;
 use rurp::interpolate::inverse_distance_weighting::interpolate;
+use rurp::point::Point;
 pub mod utils;
-use utils::{build_stub_points, CONUS_BOUNDS, STUB_BOUNDS};
+use utils::{build_empty_grid, build_stub_points, CONUS_BOUNDS, STUB_BOUNDS};
 
 #[rstest]
 #[case(1, &*STUB_BOUNDS, 1, 100, 1., 25., 0)]
@@ -22,6 +23,7 @@ fn test_interpolate(
     #[case] radius: f64,
     #[case] min_neighbors: usize,
 ) {
+    #[allow(deprecated)]
     let mut grid = Grid::empty_from_bounds(bounds, resolution, f64::NAN).unwrap();
     let points = build_stub_points(bounds, &point_count);
 
@@ -29,3 +31,19 @@ fn test_interpolate(
 
     utils::assert_grid_matches_snapshot(&grid, &format!("test_interpolate_idw_{}", case_number));
 }
+
+#[rstest]
+fn test_interpolate_points_sharing_coordinates() {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    // More points at one position and on one vertical line than fit in a kd-tree bucket.
+    let points: Vec<Point> = (0..100)
+        .map(|_| Point::new(20.5, 20.5, vec![1.]))
+        .chain((0..100).map(|idx| Point::new(80.5, f64::from(idx) + 0.5, vec![2.])))
+        .collect();
+
+    interpolate(&mut grid, &points, 2., 5., 0);
+
+    assert!((grid.data()[[20, 20, 0]] - 1.).abs() < 1e-12);
+    assert!((grid.data()[[50, 80, 0]] - 2.).abs() < 1e-12);
+    assert!(grid.data()[[50, 50, 0]].is_nan());
+}
@@ -22,6 +22,7 @@ fn test_interpolate(
     #[case] point_count: usize,
     #[case] power: f64,
 ) {
+    #[allow(deprecated)]
     let mut grid = Grid::empty_from_bounds(bounds, resolution, f64::NAN).unwrap();
     let points = build_stub_points(bounds, &point_count);
 
@@ -0,0 +1,118 @@
+use rstest::rstest;
+use rurp::interpolate::moving_window::{interpolate, Neighborhood, Statistic};
+use rurp::point::Point;
+pub mod utils;
+use utils::{build_empty_grid, build_stub_points, STUB_BOUNDS};
+
+fn build_window_points() -> Vec<Point> {
+    vec![
+        Point::new(50., 50., vec![1.]),
+        Point::new(51., 50., vec![2.]),
+        Point::new(50., 51., vec![3.]),
+        Point::new(49., 50., vec![10.]),
+        Point::new(90., 90., vec![100.]),
+    ]
+}
+
+#[rstest]
+#[case(Statistic::Count, 4.)]
+#[case(Statistic::Mean, 4.)]
+#[case(Statistic::Median, 2.5)]
+#[case(Statistic::Min, 1.)]
+#[case(Statistic::Max, 10.)]
+#[case(Statistic::Range, 9.)]
+#[case(Statistic::StandardDeviation, 3.535_533_905_932_737_6)]
+fn test_statistic_in_radius(#[case] statistic: Statistic, #[case] expected: f64) {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    let points = build_window_points();
+
+    interpolate(
+        &mut grid,
+        &points,
+        &[statistic],
+        Neighborhood::Radius(5.),
+        0,
+    );
+
+    let (x, y) = (grid.x()[[50, 50]], grid.y()[[50, 50]]);
+    assert!((x - 50.).abs() < 1. && (y - 50.).abs() < 1.);
+    assert!((grid.data()[[50, 50, 0]] - expected).abs() < 1e-12);
+}
+
+#[rstest]
+fn test_one_band_per_statistic() {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    let points = build_window_points();
+    let statistics = [Statistic::Min, Statistic::Max, Statistic::Count];
+
+    interpolate(&mut grid, &points, &statistics, Neighborhood::Nearest(2), 0);
+
+    assert_eq!(grid.bands(), 3);
+    assert_eq!(grid.data()[[90, 90, 2]], 2.);
+    assert_eq!(grid.data()[[0, 0, 2]], 2.);
+    assert!(grid.data()[[90, 90, 0]] <= grid.data()[[90, 90, 1]]);
+}
+
+#[rstest]
+fn test_min_neighbors_leaves_nodata() {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    let points = build_window_points();
+
+    interpolate(
+        &mut grid,
+        &points,
+        &[Statistic::Mean, Statistic::Count],
+        Neighborhood::Radius(5.),
+        2,
+    );
+
+    assert!(grid.data()[[0, 0, 0]].is_nan());
+    assert_eq!(grid.data()[[0, 0, 1]], 0.);
+    assert!(grid.data()[[90, 90, 0]].is_nan());
+    assert_eq!(grid.data()[[90, 90, 1]], 1.);
+    assert!(!grid.data()[[50, 50, 0]].is_nan());
+}
+
+#[rstest]
+#[case(1, Statistic::Mean)]
+#[case(2, Statistic::StandardDeviation)]
+fn test_interpolate(#[case] case_number: usize, #[case] statistic: Statistic) {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    let points = build_stub_points(&STUB_BOUNDS, &100);
+
+    interpolate(
+        &mut grid,
+        &points,
+        &[statistic],
+        Neighborhood::Radius(25.),
+        3,
+    );
+
+    utils::assert_grid_matches_snapshot(
+        &grid,
+        &format!("test_interpolate_moving_window_{}", case_number),
+    );
+}
+
+#[rstest]
+fn test_points_sharing_coordinates() {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    // More points at one position than fit in a kd-tree bucket.
+    let points: Vec<Point> = (0..40)
+        .map(|idx| Point::new(50.5, 50.5, vec![f64::from(idx)]))
+        .collect();
+    let statistics = [Statistic::Count, Statistic::Max];
+
+    interpolate(&mut grid, &points, &statistics, Neighborhood::Radius(1.), 0);
+    assert_eq!(grid.data()[[50, 50, 0]], 40.);
+    assert_eq!(grid.data()[[50, 50, 1]], 39.);
+
+    interpolate(
+        &mut grid,
+        &points,
+        &statistics,
+        Neighborhood::Nearest(35),
+        0,
+    );
+    assert_eq!(grid.data()[[0, 0, 0]], 35.);
+}
@@ -8,6 +8,7 @@ use utils::{build_stub_points, CONUS_BOUNDS, STUB_BOUNDS};
 #[rstest]
 #[case(1, &*STUB_BOUNDS, 1, 10)]
 #[case(2, &*CONUS_BOUNDS, 4000, 8000)]
+#[ignore = "slow"]
 #[case(3, &*CONUS_BOUNDS, 2000, 16000)]
 fn test_interpolate(
     #[case] case_number: usize,
@@ -15,6 +16,7 @@ fn test_interpolate(
     #[case] resolution: usize,
     #[case] point_count: usize,
 ) {
+    #[allow(deprecated)]
     let mut grid = Grid::empty_from_bounds(bounds, resolution, f64::NAN).unwrap();
     let points = build_stub_points(bounds, &point_count);
 
@@ -1,9 +1,14 @@
+use geo::{polygon, Area, MultiPolygon};
 use rstest::rstest;
 use rurp::bounds::Bounds;
 use rurp::grid::Grid;
-use rurp::interpolate::nearest_neighbor::interpolate;
+use rurp::interpolate::nearest_neighbor::{
+    allocate, clipped_voronoi_polygons, interpolate, interpolate_kd_tree,
+    interpolate_within_distance, thiessen_weights, voronoi_polygons, Output,
+};
+use rurp::point::Point;
 pub mod utils;
-use utils::{build_stub_points, CONUS_BOUNDS, STUB_BOUNDS};
+use utils::{build_empty_grid, build_stub_points, CONUS_BOUNDS, STUB_BOUNDS};
 
 #[rstest]
 #[case(1, &*STUB_BOUNDS, 1, 10)]
@@ -15,6 +20,7 @@ fn test_interpolate(
     #[case] resolution: usize,
     #[case] point_count: usize,
 ) {
+    #[allow(deprecated)]
     let mut grid = Grid::empty_from_bounds(bounds, resolution, f64::NAN).unwrap();
     let points = build_stub_points(bounds, &point_count);
 
@@ -28,6 +34,7 @@ fn test_interpolate(
 
 #[rstest]
 fn test_error_on_empty_points() {
+    #[allow(deprecated)]
     let mut grid = Grid::empty_from_bounds(&STUB_BOUNDS, 1, f64::NAN).unwrap();
     let points = vec![];
 
@@ -39,3 +46,187 @@ fn test_error_on_empty_points() {
         .to_string()
         .contains("No points to interpolate"));
 }
+
+#[rstest]
+fn test_kd_tree_error_on_empty_points() {
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+
+    let result = interpolate_kd_tree(&mut grid, &[], None);
+
+    assert!(result.is_err());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("No points to interpolate"));
+}
+
+#[rstest]
+#[case(1, &*STUB_BOUNDS, 1., 10, None)]
+#[case(2, &*STUB_BOUNDS, 1., 10, Some(15.))]
+#[case(3, &*CONUS_BOUNDS, 4000., 8000, Some(50_000.))]
+fn test_interpolate_kd_tree(
+    #[case] case_number: usize,
+    #[case] bounds: &Bounds,
+    #[case] resolution: f64,
+    #[case] point_count: usize,
+    #[case] max_distance: Option<f64>,
+) {
+    let mut grid = build_empty_grid(bounds, resolution, f64::NAN);
+    let points = build_stub_points(bounds, &point_count);
+
+    interpolate_kd_tree(&mut grid, &points, max_distance).unwrap();
+
+    utils::assert_grid_matches_snapshot(
+        &grid,
+        &format!("test_interpolate_nearest_kd_tree_{}", case_number),
+    );
+}
+
+#[rstest]
+fn test_max_distance() {
+    let points = vec![
+        Point::new(10., 10., vec![1.]),
+        Point::new(90., 90., vec![2.]),
+    ];
+    let mut voronoi_grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    let mut kd_tree_grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+
+    interpolate_within_distance(&mut voronoi_grid, &points, 20.).unwrap();
+    interpolate_kd_tree(&mut kd_tree_grid, &points, Some(20.)).unwrap();
+
+    for grid in [&voronoi_grid, &kd_tree_grid] {
+        assert_eq!(grid.data()[[10, 10, 0]], 1.);
+        assert_eq!(grid.data()[[90, 90, 0]], 2.);
+        assert!(grid.data()[[50, 50, 0]].is_nan());
+        assert!(grid.data()[[10, 90, 0]].is_nan());
+    }
+}
+
+#[rstest]
+fn test_kd_tree_duplicate_points() {
+    let points = vec![
+        Point::new(50., 50., vec![3.]),
+        Point::new(50., 50., vec![3.]),
+        Point::new(50., 50., vec![3.]),
+    ];
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+
+    interpolate_kd_tree(&mut grid, &points, None).unwrap();
+
+    assert!(grid.data().iter().all(|value| *value == 3.));
+}
+
+#[rstest]
+fn test_kd_tree_many_duplicate_and_collinear_points() {
+    // More points at one position and on one horizontal line than fit in a kd-tree bucket.
+    let points: Vec<Point> = (0..40)
+        .map(|_| Point::new(20.5, 20.5, vec![3.]))
+        .chain((0..100).map(|idx| Point::new(f64::from(idx) + 0.5, 80.5, vec![4.])))
+        .collect();
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+
+    interpolate_kd_tree(&mut grid, &points, Some(5.)).unwrap();
+
+    assert_eq!(grid.data()[[20, 20, 0]], 3.);
+    assert_eq!(grid.data()[[80, 50, 0]], 4.);
+    assert_eq!(grid.data()[[78, 10, 0]], 4.);
+    assert!(grid.data()[[50, 50, 0]].is_nan());
+}
+
+#[rstest]
+fn test_allocate() {
+    let points = vec![
+        Point::new(10., 10., vec![5.]),
+        Point::new(90., 10., vec![6.]),
+        Point::new(50., 90., vec![7.]),
+    ];
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+
+    allocate(
+        &mut grid,
+        &points,
+        Some(45.),
+        &[Output::Index, Output::Value, Output::Distance],
+    )
+    .unwrap();
+
+    assert_eq!(grid.bands(), 3);
+    assert_eq!(grid.data()[[10, 10, 0]], 0.);
+    assert_eq!(grid.data()[[10, 90, 0]], 1.);
+    assert_eq!(grid.data()[[90, 50, 0]], 2.);
+    assert_eq!(grid.data()[[90, 50, 1]], 7.);
+
+    let (x, y) = (grid.x()[[20, 20]], grid.y()[[20, 20]]);
+    let expected_distance = ((x - 10.).powi(2) + (y - 10.).powi(2)).sqrt();
+    assert!((grid.data()[[20, 20, 2]] - expected_distance).abs() < 1e-9);
+
+    let index_band = grid.data().index_axis(ndarray::Axis(2), 0).to_owned();
+    assert!(index_band
+        .iter()
+        .all(|index| index.is_nan() || [0., 1., 2.].contains(index)));
+    assert!(grid.data()[[99, 0, 0]].is_nan());
+    assert!(grid.data()[[99, 0, 2]].is_nan());
+}
+
+#[rstest]
+fn test_voronoi_polygons_cover_bounds() {
+    let points = build_stub_points(&STUB_BOUNDS, &25);
+
+    let polygons = voronoi_polygons(&points, &STUB_BOUNDS).unwrap();
+
+    assert_eq!(polygons.len(), points.len());
+    let total_area: f64 = polygons.iter().map(Area::unsigned_area).sum();
+    assert!((total_area - 100. * 100.).abs() < 1e-6);
+}
+
+#[rstest]
+fn test_clipped_voronoi_polygons() {
+    let points = vec![
+        Point::new(25., 50., vec![1.]),
+        Point::new(75., 50., vec![2.]),
+    ];
+    let region: MultiPolygon =
+        polygon![(x: 40., y: 0.), (x: 100., y: 0.), (x: 100., y: 10.), (x: 40., y: 10.)].into();
+
+    let polygons = clipped_voronoi_polygons(&points, &region).unwrap();
+
+    assert_eq!(polygons.len(), 2);
+    assert!(
+        (polygons[0].unsigned_area() - 100.).abs() < 1e-6,
+        "{}",
+        polygons[0].unsigned_area()
+    );
+    assert!((polygons[1].unsigned_area() - 500.).abs() < 1e-6);
+}
+
+#[rstest]
+#[case(polygon![(x: 0., y: 0.), (x: 100., y: 0.), (x: 100., y: 100.), (x: 0., y: 100.)], [0.5, 0.5, 0.])]
+#[case(polygon![(x: 10., y: 10.), (x: 40., y: 10.), (x: 40., y: 40.), (x: 10., y: 40.)], [1., 0., 0.])]
+#[case(polygon![(x: 40., y: 0.), (x: 70., y: 0.), (x: 70., y: 10.), (x: 40., y: 10.)], [1. / 3., 2. / 3., 0.])]
+fn test_thiessen_weights(#[case] region: geo::Polygon<f64>, #[case] expected_weights: [f64; 3]) {
+    let points = vec![
+        Point::new(25., 50., vec![1.]),
+        Point::new(75., 50., vec![2.]),
+        Point::new(500., 500., vec![3.]),
+    ];
+
+    let weights = thiessen_weights(&points, &region.into()).unwrap();
+
+    assert_eq!(weights.len(), 3);
+    for (weight, expected_weight) in weights.iter().zip(expected_weights) {
+        assert!((weight - expected_weight).abs() < 1e-9);
+    }
+}
+
+#[rstest]
+fn test_thiessen_weights_error_on_empty_region() {
+    let points = vec![Point::new(25., 50., vec![1.])];
+
+    let result = thiessen_weights(&points, &MultiPolygon::new(vec![]));
+
+    assert!(result.is_err());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("Region has no area"));
+}
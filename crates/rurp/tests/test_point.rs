@@ -47,3 +47,10 @@ fn test_into_delaunator_point() {
     assert_eq!(delaunator_point.x, point.x);
     assert_eq!(delaunator_point.y, point.y);
 }
+
+#[test]
+fn test_new_at_time() {
+    let point = Point::new_at_time(100., -20., 1_704_067_200, vec![1.]);
+    assert_eq!(point.time, Some(1_704_067_200));
+    assert_eq!(Point::new(100., -20., vec![1.]).time, None);
+}
@@ -0,0 +1,53 @@
+use rstest::rstest;
+use rurp::resolution::Resolution;
+
+#[rstest]
+#[case(0.25, 0.25)]
+#[case(4000., 2000.)]
+fn test_new(#[case] x: f64, #[case] y: f64) {
+    let resolution = Resolution::new(x, y).expect("Failed to create resolution");
+    assert_eq!(resolution.x(), x);
+    assert_eq!(resolution.y(), y);
+}
+
+#[rstest]
+#[case::zero(0.)]
+#[case::negative(-1.)]
+#[case::nan(f64::NAN)]
+#[case::infinite(f64::INFINITY)]
+fn test_error_if_not_positive_and_finite(#[case] size: f64) {
+    let result = Resolution::new(size, 1.);
+    assert!(result.is_err());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("x resolution must be positive and finite"));
+
+    let result = Resolution::new(1., size);
+    assert!(result.is_err());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("y resolution must be positive and finite"));
+}
+
+#[rstest]
+fn test_square() {
+    let resolution = Resolution::square(0.5).unwrap();
+    assert_eq!(resolution.x(), 0.5);
+    assert_eq!(resolution.y(), 0.5);
+}
+
+#[rstest]
+fn test_from_f64() {
+    let resolution: Resolution = 0.125.try_into().unwrap();
+    assert_eq!(resolution.x(), 0.125);
+    assert_eq!(resolution.y(), 0.125);
+}
+
+#[rstest]
+fn test_from_tuple() {
+    let resolution: Resolution = (0.25, 0.5).try_into().unwrap();
+    assert_eq!(resolution.x(), 0.25);
+    assert_eq!(resolution.y(), 0.5);
+}
@@ -0,0 +1,188 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rstest::rstest;
+use rurp::bounds::Bounds;
+use rurp::interpolate::space_time::{interpolate, SpaceTimeInverseDistanceWeighting};
+use rurp::interpolate::{inverse_distance_weighting, Interpolator};
+use rurp::point::Point;
+use rurp::{equivalent, Timestamp};
+pub mod utils;
+use utils::{build_empty_grid, build_stub_points, CONUS_BOUNDS, STUB_BOUNDS};
+
+/// 2024-01-01T00:00:00Z
+const TIME: Timestamp = 1_704_067_200;
+
+/// Build stub points observed at random times within an hour of `TIME`.
+fn build_timestamped_points(bounds: &Bounds, point_count: usize) -> Vec<Point> {
+    let mut rng = StdRng::seed_from_u64(8093);
+    build_stub_points(bounds, &point_count)
+        .into_iter()
+        .map(|point| Point {
+            time: Some(TIME + rng.gen_range(-3600..=3600)),
+            ..point
+        })
+        .collect()
+}
+
+#[rstest]
+#[case(1, &*STUB_BOUNDS, 1., 100, 0.01, 25.)]
+#[case(2, &*STUB_BOUNDS, 1., 100, 0.1, 100.)]
+#[case(3, &*CONUS_BOUNDS, 16000., 10000, 50., 250_000.)]
+fn test_interpolate(
+    #[case] case_number: usize,
+    #[case] bounds: &Bounds,
+    #[case] resolution: f64,
+    #[case] point_count: usize,
+    #[case] time_scale: f64,
+    #[case] radius: f64,
+) {
+    let mut grid = build_empty_grid(bounds, resolution, f64::NAN);
+    let points = build_timestamped_points(bounds, point_count);
+
+    interpolate(&mut grid, &points, TIME, time_scale, 2., radius, 0).unwrap();
+
+    utils::assert_grid_matches_snapshot(
+        &grid,
+        &format!("test_interpolate_space_time_{}", case_number),
+    );
+}
+
+#[rstest]
+fn test_zero_time_scale_matches_idw() {
+    let points = build_timestamped_points(&STUB_BOUNDS, 100);
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    let mut expected = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+
+    interpolate(&mut grid, &points, TIME, 0., 2., 25., 0).unwrap();
+    inverse_distance_weighting::interpolate(&mut expected, &points, 2., 25., 0);
+
+    for (value, expected) in grid.data().iter().zip(expected.data()) {
+        assert!(equivalent(value, expected) || (value - expected).abs() < 1e-9);
+    }
+}
+
+#[rstest]
+#[case(0.)]
+#[case(0.01)]
+fn test_many_points_sharing_a_time_match_idw(#[case] time_scale: f64) {
+    // More points at one time than fit in a kd-tree bucket, which all share one time offset.
+    let points: Vec<Point> = build_stub_points(&STUB_BOUNDS, &2000)
+        .into_iter()
+        .map(|point| Point {
+            time: Some(TIME),
+            ..point
+        })
+        .collect();
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    let mut expected = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+
+    interpolate(&mut grid, &points, TIME, time_scale, 2., 10., 0).unwrap();
+    inverse_distance_weighting::interpolate(&mut expected, &points, 2., 10., 0);
+
+    for (value, expected) in grid.data().iter().zip(expected.data()) {
+        assert!(equivalent(value, expected) || (value - expected).abs() < 1e-9);
+    }
+}
+
+#[rstest]
+fn test_zero_time_scale_with_many_points() {
+    let points = build_timestamped_points(&STUB_BOUNDS, 2000);
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+    let mut expected = build_empty_grid(&STUB_BOUNDS, 1., f64::NAN);
+
+    interpolate(&mut grid, &points, TIME, 0., 2., 10., 0).unwrap();
+    inverse_distance_weighting::interpolate(&mut expected, &points, 2., 10., 0);
+
+    for (value, expected) in grid.data().iter().zip(expected.data()) {
+        assert!(equivalent(value, expected) || (value - expected).abs() < 1e-9);
+    }
+}
+
+#[rstest]
+#[case(0, 0.01, (10. + 40. / 325.) / (1. + 1. / 325.))]
+#[case(1800, 0.01, (10. / 325. + 40.) / (1. / 325. + 1.))]
+#[case(3600, 0.01, 40.)]
+#[case(0, 0., 25.)]
+fn test_nearer_times_weigh_more(
+    #[case] offset: Timestamp,
+    #[case] time_scale: f64,
+    #[case] expected: f64,
+) {
+    // Both points are 1 unit from the location; the second is observed 30 minutes later,
+    // which is 18 units away in time, so its squared distance is 1 + 18 ^ 2 = 325 at `TIME`.
+    let points = vec![
+        Point::new_at_time(49., 50., TIME, vec![10.]),
+        Point::new_at_time(51., 50., TIME + 1800, vec![40.]),
+    ];
+    let interpolator = SpaceTimeInverseDistanceWeighting {
+        time: TIME + offset,
+        time_scale,
+        power: 2.,
+        radius: 30.,
+        min_neighbors: 1,
+    };
+
+    let predicted = interpolator.predict(&points, &[(50., 50.)]).unwrap();
+
+    assert!(
+        (predicted[0].unwrap() - expected).abs() < 1e-3,
+        "{predicted:?}"
+    );
+}
+
+#[rstest]
+fn test_predict_matches_interpolate() {
+    let points = build_timestamped_points(&STUB_BOUNDS, 100);
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 5., f64::NAN);
+    interpolate(&mut grid, &points, TIME, 0.01, 2., 25., 2).unwrap();
+    let interpolator = SpaceTimeInverseDistanceWeighting {
+        time: TIME,
+        time_scale: 0.01,
+        power: 2.,
+        radius: 25.,
+        min_neighbors: 2,
+    };
+    let locations: Vec<(f64, f64)> = grid
+        .x()
+        .iter()
+        .copied()
+        .zip(grid.y().iter().copied())
+        .collect();
+
+    let predicted = interpolator.predict(&points, &locations).unwrap();
+
+    for (value, expected) in predicted.iter().zip(grid.data()) {
+        match value {
+            Some(value) => assert!((value - expected).abs() < 1e-9),
+            None => assert!(expected.is_nan()),
+        }
+    }
+}
+
+#[rstest]
+fn test_points_without_time() {
+    let mut points = build_timestamped_points(&STUB_BOUNDS, 10);
+    points.push(Point::new(50., 50., vec![1.]));
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 10., f64::NAN);
+
+    let result = interpolate(&mut grid, &points, TIME, 0.01, 2., 25., 0);
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Every point must have a time"
+    );
+}
+
+#[rstest]
+#[case(-1.)]
+#[case(f64::NAN)]
+fn test_invalid_time_scale(#[case] time_scale: f64) {
+    let points = build_timestamped_points(&STUB_BOUNDS, 10);
+    let mut grid = build_empty_grid(&STUB_BOUNDS, 10., f64::NAN);
+
+    let result = interpolate(&mut grid, &points, TIME, time_scale, 2., 25., 0);
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Time scale must be non-negative and finite"
+    );
+}
@@ -1,8 +1,10 @@
 use once_cell::sync::Lazy;
 use rand::{distributions::Uniform, rngs::StdRng, Rng, SeedableRng};
 use rurp::bounds::Bounds;
-use rurp::grid::Grid;
+use rurp::cell_value::CellValue;
+use rurp::grid::{Grid, Registration};
 use rurp::point::Point;
+use rurp::resolution::{ExtentPolicy, Resolution};
 
 pub static STUB_BOUNDS: Lazy<Bounds> = Lazy::new(|| Bounds::new(0., 0., 100., 100.).unwrap());
 
@@ -27,6 +29,55 @@ pub fn build_stub_points(bounds: &Bounds, point_count: &usize) -> Vec<Point> {
         .collect()
 }
 
+/// Build an empty grid of square cells `resolution` wide, expanded to cover `bounds`.
+#[must_use]
+pub fn build_empty_grid<T: CellValue>(bounds: &Bounds, resolution: f64, nodata: T) -> Grid<T> {
+    let resolution = Resolution::square(resolution).unwrap();
+    Grid::empty_from_resolution(
+        bounds,
+        &resolution,
+        ExtentPolicy::Expand,
+        Registration::PixelIsArea,
+        nodata,
+    )
+    .unwrap()
+}
+
+/// Build a grid like `build_empty_grid` with each cell set from its row and column.
+#[must_use]
+pub fn build_indexed_grid(
+    bounds: &Bounds,
+    resolution: f64,
+    value: impl Fn(usize, usize) -> f64,
+) -> Grid {
+    let mut grid = build_empty_grid(bounds, resolution, f64::NAN);
+    grid.data_mut()
+        .indexed_iter_mut()
+        .for_each(|((row, column, _), cell)| *cell = value(row, column));
+    grid
+}
+
+/// Build a grid like `build_empty_grid` with each cell set from the world coordinates of its center.
+#[must_use]
+pub fn build_world_grid(bounds: &Bounds, resolution: f64, value: impl Fn(f64, f64) -> f64) -> Grid {
+    let mut grid = build_empty_grid(bounds, resolution, f64::NAN);
+    grid.iter_world_mut()
+        .for_each(|(x, y, cell)| *cell = value(x, y));
+    grid
+}
+
+/// Build a grid of unit cells over `STUB_BOUNDS` holding the plane `2 * column + 3 * row`.
+#[must_use]
+pub fn build_plane_grid() -> Grid {
+    build_indexed_grid(&STUB_BOUNDS, 1., |row, column| {
+        2. * column as f64 + 3. * row as f64
+    })
+}
+
+pub fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+}
+
 pub fn assert_grid_matches_snapshot(grid: &Grid, snapshot_id: &str) {
     let cwd = std::env::current_dir().unwrap();
     let file_path = cwd